@@ -1,19 +1,28 @@
+use std::collections::HashSet;
+
 use either::{for_both, Either};
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote, ToTokens};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
-    DeriveInput, Error, GenericArgument, Ident, LitStr, Member, PathArguments, Result, Type,
-    Visibility,
+    ext::IdentExt, spanned::Spanned, Data, DeriveInput, Error, Fields, GenericArgument, Generics,
+    Ident, LitStr, Member, PathArguments, Result, Type, Visibility,
 };
 
-use crate::thiserror::ast::{Field, Input, Variant};
+use crate::thiserror::ast::{Field, Input};
 
 struct Args {
     other_args: Vec<TokenStream>,
     other_names: Vec<Ident>,
     other_tys: Vec<Type>,
     source_arg: Option<TokenStream>,
+    source_name: Option<Ident>,
     ctor_args: Vec<TokenStream>,
+    /// The constructor arguments without the source, if the source is optional
+    /// and converted with `Into`.
+    no_source_ctor_args: Option<Vec<TokenStream>>,
+    /// The generic parameters of the arguments converted with `TryInto`, with
+    /// the target types, if the constructor is fallible.
+    try_params: Vec<(Ident, Type)>,
 }
 
 enum SourceInto {
@@ -21,14 +30,18 @@ enum SourceInto {
     No,
 }
 
-fn resolve_variant_args(variant: &Variant<'_>, source_into: SourceInto) -> Args {
+fn resolve_variant_args(fields: &[Field<'_>], source_into: SourceInto, try_into: bool) -> Args {
     let mut other_args = Vec::new();
     let mut other_names = Vec::new();
     let mut other_tys = Vec::new();
     let mut source_arg = None;
+    let mut source_name = None;
     let mut ctor_args = Vec::new();
+    let mut no_source_ctor_args = Vec::new();
+    let mut optional_source = false;
+    let mut try_params = Vec::new();
 
-    for (i, field) in variant.fields.iter().enumerate() {
+    for (i, field) in fields.iter().enumerate() {
         let ty = &field.ty;
         let member = &field.member;
 
@@ -43,6 +56,10 @@ fn resolve_variant_args(variant: &Variant<'_>, source_into: SourceInto) -> Args
             }
         };
 
+        if field.is_non_from_source() {
+            source_name = Some(name.clone());
+        }
+
         if field.is_backtrace() {
             let expr = if type_is_option(ty) {
                 quote!(std::option::Option::Some(
@@ -53,23 +70,36 @@ fn resolve_variant_args(variant: &Variant<'_>, source_into: SourceInto) -> Args
                     std::backtrace::Backtrace::capture()
                 ))
             };
-            ctor_args.push(quote!(#member: #expr,))
+            ctor_args.push(quote!(#member: #expr,));
+            no_source_ctor_args.push(quote!(#member: #expr,));
         } else if field.is_non_from_source() {
-            match source_into {
-                SourceInto::Yes => {
-                    source_arg = Some(quote!(#name: impl Into<#ty>,));
+            match (&source_into, type_parameter_of_option(ty)) {
+                // Accept the inner type for an optional source, instead of the
+                // awkward `impl Into<Option<E>>`.
+                (SourceInto::Yes, Some(inner_ty)) => {
+                    optional_source = true;
+                    let arg_ty = ctor_arg_ty(field, inner_ty);
+                    source_arg = Some(quote!(#name: #arg_ty,));
+                    ctor_args.push(quote!(#member: std::option::Option::Some(#name.into()),));
+                    no_source_ctor_args.push(quote!(#member: std::option::Option::None,));
+                }
+                (SourceInto::Yes, None) => {
+                    let arg_ty = ctor_arg_ty(field, ty);
+                    source_arg = Some(quote!(#name: #arg_ty,));
                     ctor_args.push(quote!(#member: #name.into(),));
                 }
-                SourceInto::No => {
+                (SourceInto::No, _) => {
                     source_arg = Some(quote!(#name: #ty,));
                     ctor_args.push(quote!(#member: #name,));
                 }
             }
         } else {
-            other_args.push(quote!(#name: impl Into<#ty>,));
+            let (arg_ty, expr) = ctor_arg(field, ty, &name, try_into, &mut try_params);
+            other_args.push(quote!(#name: #arg_ty,));
             other_names.push(name.clone());
             other_tys.push((**ty).clone());
-            ctor_args.push(quote!(#member: #name.into(),));
+            ctor_args.push(quote!(#member: #expr,));
+            no_source_ctor_args.push(quote!(#member: #expr,));
         }
     }
 
@@ -78,7 +108,48 @@ fn resolve_variant_args(variant: &Variant<'_>, source_into: SourceInto) -> Args
         other_names,
         other_tys,
         source_arg,
+        source_name,
         ctor_args,
+        no_source_ctor_args: optional_source.then_some(no_source_ctor_args),
+        try_params,
+    }
+}
+
+/// Returns the type of the constructor argument for the field and the expression
+/// converting it to the field type.
+///
+/// For a fallible constructor, the argument is of a generic type converted with
+/// `TryInto`, whose parameter is pushed to `try_params`. Otherwise, see
+/// [`ctor_arg_ty`]. The source is never converted with `TryInto`.
+fn ctor_arg(
+    field: &Field<'_>,
+    ty: &Type,
+    name: &Ident,
+    try_into: bool,
+    try_params: &mut Vec<(Ident, Type)>,
+) -> (TokenStream, TokenStream) {
+    if try_into && field.construct_into() {
+        let param = format_ident!("__T{}", try_params.len());
+        try_params.push((param.clone(), ty.clone()));
+        (
+            quote!(#param),
+            quote!(std::convert::TryInto::try_into(#name)?),
+        )
+    } else {
+        (ctor_arg_ty(field, ty), quote!(#name.into()))
+    }
+}
+
+/// Returns the type of the constructor argument for the field, which is
+/// `impl Into<T>` unless disabled with `#[construct(into = false)]`.
+///
+/// The argument is always converted with `.into()` in the constructor, which is
+/// a no-op for the exact type.
+fn ctor_arg_ty(field: &Field<'_>, ty: &Type) -> TokenStream {
+    if field.construct_into() {
+        quote!(impl Into<#ty>)
+    } else {
+        quote!(#ty)
     }
 }
 
@@ -86,12 +157,17 @@ struct MacroArgs {
     other_args: Vec<TokenStream>,
     other_call_args: Vec<TokenStream>,
     ctor_args: Vec<TokenStream>,
+    /// The constructor arguments for a message of a single string literal, if
+    /// the message field is a `Cow`, so that it can be borrowed without
+    /// allocation.
+    literal_ctor_args: Option<Vec<TokenStream>>,
 }
 
-fn resolve_args_for_macro(fields: &[Field<'_>]) -> MacroArgs {
+fn resolve_args_for_macro(fields: &[Field<'_>], message_field: &Field<'_>) -> MacroArgs {
     let mut other_args = Vec::new();
     let mut other_call_args = Vec::new();
     let mut ctor_args = Vec::new();
+    let mut literal_message = None;
 
     for (i, field) in fields.iter().enumerate() {
         let ty = &field.ty;
@@ -113,7 +189,30 @@ fn resolve_args_for_macro(fields: &[Field<'_>]) -> MacroArgs {
                 ))
             };
             ctor_args.push(quote!(#member: #expr,))
-        } else if field.is_message() {
+        } else if field.attrs.location.is_some() {
+            // The location of the outermost macro call is returned in macro expansions.
+            let expr = if let Type::Reference(_) = ty {
+                quote!(::std::panic::Location::caller())
+            } else {
+                quote!(*::std::panic::Location::caller())
+            };
+            ctor_args.push(quote!(#member: #expr,));
+        } else if field.member == message_field.member {
+            if type_is_cow(ty) {
+                // The literal may still contain inline arguments or escaped braces,
+                // fall back to `format!` in this case.
+                literal_message = Some((
+                    ctor_args.len(),
+                    quote!(#member: {
+                    const MESSAGE: &str = $message;
+                    if MESSAGE.contains(['{', '}']) {
+                        ::std::borrow::Cow::Owned(::std::format!($message))
+                    } else {
+                        ::std::borrow::Cow::Borrowed(MESSAGE)
+                    }
+                },),
+                ));
+            }
             ctor_args.push(quote!(#member: ::std::format!($($fmt_arg)*).into(),));
         } else {
             other_args.push(quote!(#name = $#name:expr,));
@@ -122,27 +221,69 @@ fn resolve_args_for_macro(fields: &[Field<'_>]) -> MacroArgs {
         }
     }
 
+    let literal_ctor_args = literal_message.map(|(i, literal_message)| {
+        let mut literal_ctor_args = ctor_args.clone();
+        literal_ctor_args[i] = literal_message;
+        literal_ctor_args
+    });
+
     MacroArgs {
         other_args,
         other_call_args,
         ctor_args,
+        literal_ctor_args,
     }
 }
 
 struct DeriveMeta {
     impl_type: Ident,
     nt_backtrace: bool,
+    nt_always_backtrace: bool,
+    nt_spantrace: bool,
+    nt_eq: Option<Span>,
+    nt_hash: Option<Span>,
+    nt_clone: Option<Span>,
+    nt_report_debug: bool,
+    nt_deref: bool,
+    nt_downcast: bool,
+    nt_diagnostic: Option<Span>,
+    nt_variant_accessors: Option<Span>,
+    nt_kind: Option<(Span, Option<Ident>)>,
+    nt_kind_alias: Option<Span>,
     macro_mangle: bool,
+    macro_no_bail: bool,
+    /// The `tracing` level of the `log_*` macros, if enabled.
+    macro_log: Option<Ident>,
+    macro_location: Option<Span>,
+    macro_prefix: String,
     macro_path: Option<TokenStream>,
     macro_vis: Option<Visibility>,
+    macro_doc: Option<LitStr>,
 }
 
 fn resolve_meta(input: &DeriveInput) -> Result<DeriveMeta> {
     let mut new_type = None;
     let mut nt_backtrace = false;
+    let mut nt_always_backtrace = false;
+    let mut nt_spantrace = false;
+    let mut nt_eq = None;
+    let mut nt_hash = None;
+    let mut nt_clone = None;
+    let mut nt_report_debug = false;
+    let mut nt_deref = false;
+    let mut nt_downcast = false;
+    let mut nt_diagnostic = None;
+    let mut nt_variant_accessors = None;
+    let mut nt_kind = None;
+    let mut nt_kind_alias = None;
     let mut macro_mangle = false;
+    let mut macro_no_bail = false;
+    let mut macro_log = None;
+    let mut macro_location = None;
+    let mut macro_prefix = String::new();
     let mut macro_path = None;
     let mut macro_vis = None;
+    let mut macro_doc = None;
 
     for attr in &input.attrs {
         if attr.path().is_ident("thiserror_ext") {
@@ -155,12 +296,64 @@ fn resolve_meta(input: &DeriveInput) -> Result<DeriveMeta> {
                         } else if meta.path.is_ident("backtrace") {
                             if cfg!(feature = "backtrace") {
                                 nt_backtrace = true;
+                                if meta.input.peek(syn::Token![=]) {
+                                    let mode: Ident = meta.value()?.parse()?;
+                                    if mode == "always" {
+                                        nt_always_backtrace = true;
+                                    } else {
+                                        return Err(Error::new_spanned(
+                                            mode,
+                                            "unknown backtrace mode, expected `always`",
+                                        ));
+                                    }
+                                }
                             } else {
                                 return Err(Error::new_spanned(
                                     meta.path,
                                     "enable the `backtrace` feature to use `backtrace` attribute",
                                 ));
                             }
+                        } else if meta.path.is_ident("spantrace") {
+                            if cfg!(feature = "tracing") {
+                                nt_spantrace = true;
+                            } else {
+                                return Err(Error::new_spanned(
+                                    meta.path,
+                                    "enable the `tracing` feature to use `spantrace` attribute",
+                                ));
+                            }
+                        } else if meta.path.is_ident("diagnostic") {
+                            if cfg!(feature = "miette") {
+                                nt_diagnostic = Some(meta.path.span());
+                            } else {
+                                return Err(Error::new_spanned(
+                                    meta.path,
+                                    "enable the `miette` feature to use `diagnostic` attribute",
+                                ));
+                            }
+                        } else if meta.path.is_ident("eq") {
+                            nt_eq = Some(meta.path.span());
+                        } else if meta.path.is_ident("hash") {
+                            nt_hash = Some(meta.path.span());
+                        } else if meta.path.is_ident("clone") {
+                            nt_clone = Some(meta.path.span());
+                        } else if meta.path.is_ident("report_debug") {
+                            nt_report_debug = true;
+                        } else if meta.path.is_ident("deref") {
+                            nt_deref = true;
+                        } else if meta.path.is_ident("downcast") {
+                            nt_downcast = true;
+                        } else if meta.path.is_ident("variant_accessors") {
+                            nt_variant_accessors = Some(meta.path.span());
+                        } else if meta.path.is_ident("kind") {
+                            let name = if meta.input.peek(syn::Token![=]) {
+                                Some(meta.value()?.parse()?)
+                            } else {
+                                None
+                            };
+                            nt_kind = Some((meta.path.span(), name));
+                        } else if meta.path.is_ident("kind_alias") {
+                            nt_kind_alias = Some(meta.path.span());
                         } else {
                             return Err(Error::new_spanned(meta.path, "unknown attribute"));
                         }
@@ -170,6 +363,42 @@ fn resolve_meta(input: &DeriveInput) -> Result<DeriveMeta> {
                     meta.parse_nested_meta(|meta| {
                         if meta.path.is_ident("mangle") {
                             macro_mangle = true;
+                        } else if meta.path.is_ident("no_bail") {
+                            macro_no_bail = true;
+                        } else if meta.path.is_ident("location") {
+                            macro_location = Some(meta.path.span());
+                        } else if meta.path.is_ident("log") {
+                            if !cfg!(feature = "tracing") {
+                                return Err(Error::new_spanned(
+                                    meta.path,
+                                    "enable the `tracing` feature to use `log` attribute",
+                                ));
+                            }
+                            let level = if meta.input.peek(syn::Token![=]) {
+                                let level: LitStr = meta.value()?.parse()?;
+                                match level.value().as_str() {
+                                    "trace" | "debug" | "info" | "warn" | "error" => {}
+                                    _ => {
+                                        return Err(Error::new_spanned(
+                                            level,
+                                            "unknown log level, expected one of `trace`, `debug`, `info`, `warn` or `error`",
+                                        ))
+                                    }
+                                }
+                                format_ident!("{}", level.value().to_uppercase(), span = level.span())
+                            } else {
+                                format_ident!("ERROR", span = meta.path.span())
+                            };
+                            macro_log = Some(level);
+                        } else if meta.path.is_ident("prefix") {
+                            let prefix: LitStr = meta.value()?.parse()?;
+                            if syn::parse_str::<Ident>(&format!("{}x", prefix.value())).is_err() {
+                                return Err(Error::new_spanned(
+                                    prefix,
+                                    "invalid macro name prefix",
+                                ));
+                            }
+                            macro_prefix = prefix.value();
                         } else if meta.path.is_ident("path") {
                             let value = meta.value()?;
                             let path: LitStr = value.parse()?;
@@ -194,6 +423,8 @@ fn resolve_meta(input: &DeriveInput) -> Result<DeriveMeta> {
                             } else {
                                 value.parse()?
                             })
+                        } else if meta.path.is_ident("doc") {
+                            macro_doc = Some(meta.value()?.parse()?);
                         } else {
                             return Err(Error::new_spanned(meta.path, "unknown attribute"));
                         }
@@ -211,9 +442,66 @@ fn resolve_meta(input: &DeriveInput) -> Result<DeriveMeta> {
     Ok(DeriveMeta {
         impl_type,
         nt_backtrace,
+        nt_always_backtrace,
+        nt_spantrace,
+        nt_eq,
+        nt_hash,
+        nt_clone,
+        nt_report_debug,
+        nt_deref,
+        nt_downcast,
+        nt_diagnostic,
+        nt_variant_accessors,
+        nt_kind,
+        nt_kind_alias,
         macro_mangle,
+        macro_no_bail,
+        macro_log,
+        macro_location,
+        macro_prefix,
         macro_path,
         macro_vis,
+        macro_doc,
+    })
+}
+
+#[derive(Default)]
+struct VariantMacroMeta {
+    doc: Option<LitStr>,
+    skip: bool,
+}
+
+/// Resolves the variant-level `#[thiserror_ext(macro(..))]` attribute, which
+/// supports `doc` and `skip`.
+fn resolve_variant_macro_meta(variant: &syn::Variant) -> Result<VariantMacroMeta> {
+    let mut macro_doc = None;
+    let mut skip = false;
+
+    for attr in &variant.attrs {
+        if attr.path().is_ident("thiserror_ext") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("macro") {
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("doc") {
+                            macro_doc = Some(meta.value()?.parse()?);
+                        } else if meta.path.is_ident("skip") {
+                            skip = true;
+                        } else {
+                            return Err(Error::new_spanned(meta.path, "unknown attribute"));
+                        }
+                        Ok(())
+                    })?;
+                } else {
+                    return Err(Error::new_spanned(meta.path, "unknown attribute"));
+                }
+                Ok(())
+            })?;
+        }
+    }
+
+    Ok(VariantMacroMeta {
+        doc: macro_doc,
+        skip,
     })
 }
 
@@ -250,6 +538,18 @@ pub fn derive_new_type(input: &DeriveInput, ty: DeriveNewType) -> Result<TokenSt
     let DeriveMeta {
         impl_type,
         nt_backtrace: backtrace,
+        nt_always_backtrace: always_backtrace,
+        nt_spantrace: spantrace,
+        nt_eq,
+        nt_hash,
+        nt_clone,
+        nt_report_debug,
+        nt_deref,
+        nt_downcast,
+        nt_diagnostic,
+        nt_variant_accessors,
+        nt_kind,
+        nt_kind_alias,
         ..
     } = resolve_meta(input)?;
 
@@ -260,27 +560,42 @@ pub fn derive_new_type(input: &DeriveInput, ty: DeriveNewType) -> Result<TokenSt
         ));
     }
 
-    let backtrace_type_param = if backtrace {
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    // The types with the generic arguments, e.g., `Inner<T>`.
+    let input_ty = quote!(#input_type #ty_generics);
+    let impl_ty = quote!(#impl_type #ty_generics);
+
+    let mut backtrace_type_param = if always_backtrace {
+        quote!(thiserror_ext::__private::AlwaysBacktrace)
+    } else if backtrace {
         quote!(thiserror_ext::__private::MaybeBacktrace)
     } else {
         quote!(thiserror_ext::__private::NoExtraBacktrace)
     };
+    if spantrace {
+        backtrace_type_param =
+            quote!(thiserror_ext::__private::WithSpanTrace<#backtrace_type_param>);
+    }
 
     let doc = format!(
-        "The `{}`-wrapped type of [`{}`].{}",
+        "The `{}`-wrapped type of [`{}`].{}{}",
         ty.name(),
         input_type,
-        if backtrace {
+        if always_backtrace {
+            "\n\nA backtrace is always captured, taking precedence over the one provided by the inner error."
+        } else if backtrace {
             "\n\nA backtrace is captured when the inner error doesn't provide one."
         } else {
             ""
+        },
+        if spantrace {
+            "\n\nA span trace is captured when the inner error doesn't provide one."
+        } else {
+            ""
         }
     );
     let new_type = ty.ty_ident();
-    let extra_derive = match ty {
-        DeriveNewType::Box => quote!(),
-        DeriveNewType::Arc => quote!(Clone),
-    };
     let backtrace_attr = if cfg!(feature = "backtrace") {
         quote!(#[backtrace])
     } else {
@@ -290,123 +605,845 @@ pub fn derive_new_type(input: &DeriveInput, ty: DeriveNewType) -> Result<TokenSt
     let into_inner = match ty {
         DeriveNewType::Box => quote!(
             #[doc = "Consumes `self` and returns the inner error."]
-            #vis fn into_inner(self) -> #input_type {
+            #vis fn into_inner(self) -> #input_ty {
                 self.0.into_inner()
             }
         ),
-        DeriveNewType::Arc => quote!(),
+        DeriveNewType::Arc => quote!(
+            #[doc = "Consumes `self` and returns the inner error, if there's no other reference to it."]
+            #[doc = ""]
+            #[doc = "Otherwise, `self` is returned unchanged as the error."]
+            #[doc(alias = "try_unwrap")]
+            #vis fn try_into_inner(self) -> std::result::Result<#input_ty, Self> {
+                self.0.try_into_inner().map_err(Self)
+            }
+
+            #[doc = "Returns a mutable reference to the inner error, if there's no other reference to it."]
+            #vis fn get_mut(&mut self) -> std::option::Option<&mut #input_ty> {
+                self.0.get_mut()
+            }
+        ),
     };
 
+    let span_trace = spantrace.then(|| {
+        quote!(
+            #[doc = "Returns the span trace captured when the error was created, if any."]
+            #vis fn span_trace(&self) -> std::option::Option<&thiserror_ext::__private::SpanTrace> {
+                self.0.span_trace()
+            }
+        )
+    });
+
+    let downcast_ref = nt_downcast.then(|| derive_downcast_ref(input, vis, &input_ty));
+
+    let (kind, kind_enum) = match (nt_kind, nt_kind_alias) {
+        (Some(_), Some(span)) => {
+            return Err(Error::new(
+                span,
+                "`kind_alias` conflicts with `kind`, which makes `kind` return the generated enum instead",
+            ))
+        }
+        (Some((span, name)), None) => {
+            let name = name.unwrap_or_else(|| format_ident!("{}Kind", impl_type, span = span));
+            derive_kind(input, vis, &name, span)?
+        }
+        (None, Some(_)) => {
+            let into_kind = matches!(ty, DeriveNewType::Box).then(|| {
+                quote!(
+                    #[doc = "Consumes `self` and returns the inner error. Alias of [`into_inner`](Self::into_inner)."]
+                    #vis fn into_kind(self) -> #input_ty {
+                        self.into_inner()
+                    }
+                )
+            });
+            (
+                quote!(
+                    #[doc = "Returns the reference to the inner error. Alias of [`inner`](Self::inner)."]
+                    #vis fn kind(&self) -> &#input_ty {
+                        self.inner()
+                    }
+                    #into_kind
+                ),
+                quote!(),
+            )
+        }
+        (None, None) => (quote!(), quote!()),
+    };
+
+    let (variant_accessors, variant_ref_structs) = match nt_variant_accessors {
+        Some(span) if !generics.params.is_empty() => {
+            return Err(Error::new(
+                span,
+                "`variant_accessors` is not supported for generic types",
+            ))
+        }
+        Some(span) => derive_variant_accessors(input, vis, span)?,
+        None => (quote!(), quote!()),
+    };
+
+    let eq_impl = nt_eq.map(|span| {
+        // Assert the bound with the span of `eq` for a helpful error message. The
+        // generic types can only be checked where they're instantiated.
+        let assert_eq = generics.params.is_empty().then(|| {
+            quote_spanned!(span=>
+                const _: fn() = || {
+                    fn assert_partial_eq<T: ?Sized + std::cmp::PartialEq>() {}
+                    assert_partial_eq::<#input_type>();
+                };
+            )
+        });
+        let partial_eq_where =
+            where_clause_with(generics, [quote!(#input_ty: std::cmp::PartialEq)]);
+        let eq_where = where_clause_with(generics, [quote!(for<'__a> #input_ty: std::cmp::Eq)]);
+
+        quote!(
+            #assert_eq
+
+            impl #impl_generics std::cmp::PartialEq for #impl_ty #partial_eq_where {
+                fn eq(&self, other: &Self) -> bool {
+                    self.inner() == other.inner()
+                }
+            }
+
+            // The higher-ranked bound is not checked eagerly, so `Eq` is only
+            // implemented if the inner type implements it.
+            impl #impl_generics std::cmp::Eq for #impl_ty #eq_where {}
+        )
+    });
+
+    let debug_impl = if nt_report_debug {
+        // Same as `ReportDebug`, but on the new type so that the backtrace
+        // captured by the new type is also shown.
+        let debug_where = where_clause_with(generics, [quote!(#input_ty: std::error::Error)]);
+        quote!(
+            impl #impl_generics std::fmt::Debug for #impl_ty #debug_where {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    use thiserror_ext::AsReport;
+                    std::fmt::Debug::fmt(&self.as_report(), f)
+                }
+            }
+        )
+    } else {
+        let debug_where = where_clause_with(generics, [quote!(#input_ty: std::fmt::Debug)]);
+        quote!(
+            impl #impl_generics std::fmt::Debug for #impl_ty #debug_where {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Debug::fmt(&self.0, f)
+                }
+            }
+        )
+    };
+
+    let clone_impl = match (nt_clone, ty) {
+        (None, DeriveNewType::Box) => None,
+        // Not derived, which would require the generic parameters to be `Clone`.
+        (None, DeriveNewType::Arc) => Some(quote!(
+            impl #impl_generics std::clone::Clone for #impl_ty #where_clause {
+                fn clone(&self) -> Self {
+                    Self(std::clone::Clone::clone(&self.0))
+                }
+            }
+        )),
+        (Some(span), DeriveNewType::Arc) => return Err(Error::new(
+            span,
+            "`clone` is only supported for `Box` newtypes, as `Arc` newtypes are always `Clone`",
+        )),
+        (Some(span), DeriveNewType::Box) => {
+            // Assert the bound with the span of `clone` for a helpful error message.
+            let assert_clone = generics.params.is_empty().then(|| {
+                quote_spanned!(span=>
+                    const _: fn() = || {
+                        fn assert_clone<T: std::clone::Clone>() {}
+                        assert_clone::<#input_type>();
+                    };
+                )
+            });
+            let clone_where = where_clause_with(generics, [quote!(#input_ty: std::clone::Clone)]);
+
+            Some(quote!(
+                #assert_clone
+
+                impl #impl_generics std::clone::Clone for #impl_ty #clone_where {
+                    fn clone(&self) -> Self {
+                        Self(std::clone::Clone::clone(&self.0))
+                    }
+                }
+            ))
+        }
+    };
+
+    let deref_impl = nt_deref.then(|| {
+        quote!(
+            impl #impl_generics std::ops::Deref for #impl_ty #where_clause {
+                type Target = #input_ty;
+
+                fn deref(&self) -> &Self::Target {
+                    self.inner()
+                }
+            }
+        )
+    });
+
+    let hash_impl = nt_hash.map(|span| {
+        // There's no way to emit a warning from a proc-macro on stable, so use a
+        // deprecated item instead.
+        let warn_without_eq = nt_eq.is_none().then(|| {
+            quote_spanned!(span=>
+                const _: () = {
+                    #[deprecated(note = "`hash` is specified without `eq`, \
+                                         while `Hash` should be consistent with `PartialEq`")]
+                    const HASH_WITHOUT_EQ: () = ();
+                    HASH_WITHOUT_EQ
+                };
+            )
+        });
+
+        // Assert the bound with the span of `hash` for a helpful error message. The
+        // generic types can only be checked where they're instantiated.
+        let assert_hash = generics.params.is_empty().then(|| {
+            quote_spanned!(span=>
+                const _: fn() = || {
+                    fn assert_hash<T: ?Sized + std::hash::Hash>() {}
+                    assert_hash::<#input_type>();
+                };
+            )
+        });
+        // The higher-ranked bound is not checked eagerly, so that an unsatisfied
+        // bound is only reported by the assertion above.
+        let hash_where =
+            where_clause_with(generics, [quote!(for<'__a> #input_ty: std::hash::Hash)]);
+
+        quote!(
+            #warn_without_eq
+            #assert_hash
+
+            impl #impl_generics std::hash::Hash for #impl_ty #hash_where {
+                fn hash<__H: std::hash::Hasher>(&self, state: &mut __H) {
+                    std::hash::Hash::hash(self.inner(), state)
+                }
+            }
+        )
+    });
+
+    let diagnostic_impl = nt_diagnostic.map(|span| {
+        // Assert the bound with the span of `diagnostic` for a helpful error message.
+        // The generic types can only be checked where they're instantiated.
+        let assert_diagnostic = generics.params.is_empty().then(|| {
+            quote_spanned!(span=>
+                const _: fn() = || {
+                    fn assert_diagnostic<T: ?Sized + thiserror_ext::__private::miette::Diagnostic>() {}
+                    assert_diagnostic::<#input_type>();
+                };
+            )
+        });
+        let diagnostic_where = where_clause_with(
+            generics,
+            [quote!(#input_ty: thiserror_ext::__private::miette::Diagnostic)],
+        );
+        quote!(
+            #assert_diagnostic
+
+            impl #impl_generics thiserror_ext::__private::miette::Diagnostic for #impl_ty
+            #diagnostic_where
+            {
+                fn code<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>> {
+                    self.inner().code()
+                }
+
+                fn severity(&self) -> std::option::Option<thiserror_ext::__private::miette::Severity> {
+                    self.inner().severity()
+                }
+
+                fn help<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>> {
+                    self.inner().help()
+                }
+
+                fn url<'a>(&'a self) -> std::option::Option<std::boxed::Box<dyn std::fmt::Display + 'a>> {
+                    self.inner().url()
+                }
+
+                fn source_code(&self) -> std::option::Option<&dyn thiserror_ext::__private::miette::SourceCode> {
+                    self.inner().source_code()
+                }
+
+                fn labels(
+                    &self,
+                ) -> std::option::Option<
+                    std::boxed::Box<dyn std::iter::Iterator<Item = thiserror_ext::__private::miette::LabeledSpan> + '_>,
+                > {
+                    self.inner().labels()
+                }
+
+                fn related<'a>(
+                    &'a self,
+                ) -> std::option::Option<
+                    std::boxed::Box<dyn std::iter::Iterator<Item = &'a dyn thiserror_ext::__private::miette::Diagnostic> + 'a>,
+                > {
+                    self.inner().related()
+                }
+
+                fn diagnostic_source(&self) -> std::option::Option<&dyn thiserror_ext::__private::miette::Diagnostic> {
+                    self.inner().diagnostic_source()
+                }
+            }
+        )
+    });
+
+    // Append the type parameter of the source error for the `From` impl.
+    let mut from_generics = generics.clone();
+    from_generics.params.push(syn::parse_quote!(__E));
+    let (from_impl_generics, _, _) = from_generics.split_for_impl();
+    let from_where = where_clause_with(
+        generics,
+        [
+            quote!(__E: Into<#input_ty>),
+            quote!(#input_ty: std::error::Error),
+        ],
+    );
+
     let generated = quote!(
         #[doc = #doc]
-        #[derive(thiserror_ext::__private::thiserror::Error, #extra_derive)]
+        #[derive(thiserror_ext::__private::thiserror::Error)]
         #[error(transparent)]
-        #vis struct #impl_type(
+        #vis struct #impl_type #generics (
             #[from]
             #backtrace_attr
             thiserror_ext::__private::#new_type<
-                #input_type,
+                #input_ty,
                 #backtrace_type_param,
             >,
-        );
+        ) #where_clause;
 
         // For `?` to work.
-        impl<E> From<E> for #impl_type
-        where
-            E: Into<#input_type>,
-        {
-            fn from(error: E) -> Self {
+        impl #from_impl_generics From<__E> for #impl_ty #from_where {
+            fn from(error: __E) -> Self {
                 Self(thiserror_ext::__private::#new_type::new(error.into()))
             }
         }
 
-        impl std::fmt::Debug for #impl_type {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                std::fmt::Debug::fmt(&self.0, f)
-            }
-        }
+        #debug_impl
 
-        impl #impl_type {
+        impl #impl_generics #impl_ty #where_clause {
             #[doc = "Returns the reference to the inner error."]
-            #vis fn inner(&self) -> &#input_type {
+            #vis fn inner(&self) -> &#input_ty {
                 self.0.inner()
             }
 
+            #kind
+
             #into_inner
+
+            #span_trace
+
+            #downcast_ref
+
+            #variant_accessors
         }
+
+        #variant_ref_structs
+        #kind_enum
+
+        #clone_impl
+        #deref_impl
+        #eq_impl
+        #hash_impl
+        #diagnostic_impl
     );
 
     Ok(generated)
 }
 
+/// Generates the discriminant enum mirroring the variants of the inner enum, and
+/// the `kind` method returning it.
+fn derive_kind(
+    input: &DeriveInput,
+    vis: &Visibility,
+    name: &Ident,
+    span: Span,
+) -> Result<(TokenStream, TokenStream)> {
+    let input_type = &input.ident;
+
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return Err(Error::new(span, "`kind` is only supported for enums")),
+    };
+
+    let variant_names: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let variant_docs = variant_names
+        .iter()
+        .map(|variant_name| format!("The kind of [`{input_type}::{variant_name}`]."));
+    let doc = format!("The kinds of [`{input_type}`], without the fields of the variants.");
+
+    let kind_enum = quote!(
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        #vis enum #name {
+            #(
+                #[doc = #variant_docs]
+                #variant_names,
+            )*
+        }
+    );
+
+    let kind = quote!(
+        #[doc = "Returns the kind of the inner error."]
+        #vis fn kind(&self) -> #name {
+            match self.inner() {
+                #(#input_type::#variant_names { .. } => #name::#variant_names,)*
+            }
+        }
+    );
+
+    Ok((kind, kind_enum))
+}
+
+/// Generates the `downcast_ref` method that looks for an error of the given type
+/// in the fields of the inner error and its sources.
+fn derive_downcast_ref(
+    input: &DeriveInput,
+    vis: &Visibility,
+    input_ty: &TokenStream,
+) -> TokenStream {
+    let input_type = &input.ident;
+
+    let find_in_fields = |path: TokenStream, fields: &Fields| {
+        let members: Vec<_> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            })
+            .collect();
+        let bindings: Vec<_> = (0..members.len())
+            .map(|i| format_ident!("__{}", i))
+            .collect();
+        let len = members.len();
+
+        quote!(
+            #path { #(#members: #bindings,)* } => {
+                let fields: [&dyn std::any::Any; #len] = [#(#bindings,)*];
+                fields.into_iter().find_map(|field| field.downcast_ref::<__T>())
+            }
+        )
+    };
+
+    let arms = match &input.data {
+        Data::Struct(data) => vec![find_in_fields(quote!(#input_type), &data.fields)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = &variant.ident;
+                find_in_fields(quote!(#input_type::#variant_name), &variant.fields)
+            })
+            .collect(),
+        Data::Union(_) => vec![],
+    };
+
+    quote!(
+        #[doc = "Returns the reference to the error of type `T` if it's found in the fields of"]
+        #[doc = "the inner error, or in its sources. The shallowest one is returned if there"]
+        #[doc = "are multiple errors of type `T`."]
+        #[doc = ""]
+        #[doc = "This also finds the errors wrapped with `#[error(transparent)]`, which are"]
+        #[doc = "not exposed as sources."]
+        #vis fn downcast_ref<__T: std::error::Error + 'static>(&self) -> std::option::Option<&__T>
+        where
+            #input_ty: 'static,
+            Self: std::error::Error,
+        {
+            let inner: &dyn std::any::Any = self.inner();
+            if let std::option::Option::Some(inner) = inner.downcast_ref::<__T>() {
+                return std::option::Option::Some(inner);
+            }
+
+            let found: std::option::Option<&__T> = match self.inner() {
+                #(#arms)*
+            };
+            found.or_else(|| thiserror_ext::AsReport::find_source::<__T>(self))
+        }
+    )
+}
+
+/// Generates the `is_*` predicate methods and the `as_*` reference accessor
+/// methods for each variant of the inner enum, returning the methods and the
+/// reference structs for variants with named fields.
+fn derive_variant_accessors(
+    input: &DeriveInput,
+    vis: &Visibility,
+    span: Span,
+) -> Result<(TokenStream, TokenStream)> {
+    let input_type = &input.ident;
+
+    let variants = match Input::from_syn(input)? {
+        Input::Enum(input) => input.variants,
+        Input::Struct(_) => {
+            return Err(Error::new(
+                span,
+                "`variant_accessors` is only supported for enums",
+            ))
+        }
+    };
+
+    let mut accessors = Vec::new();
+    let mut ref_structs = Vec::new();
+
+    // Variants with `#[from]` are transparent wrappers of other errors.
+    for variant in variants.iter().filter(|v| v.from_field().is_none()) {
+        let variant_name = &variant.ident;
+        let snake_name = big_camel_case_to_snake_case(&variant_name.unraw().to_string());
+        let is_name = format_ident!("is_{}", snake_name, span = variant_name.span());
+        let as_name = format_ident!("as_{}", snake_name, span = variant_name.span());
+
+        let is_doc = format!(
+            "Returns `true` if the inner error is a [`{input_type}::{variant_name}`] variant."
+        );
+        accessors.push(quote!(
+            #[doc = #is_doc]
+            #vis fn #is_name(&self) -> bool {
+                matches!(self.inner(), #input_type::#variant_name { .. })
+            }
+        ));
+
+        let bindings: Vec<_> = (0..variant.fields.len())
+            .map(|i| format_ident!("__{}", i))
+            .collect();
+        let members = variant.fields.iter().map(|f| &f.member);
+        let pattern = quote!(#input_type::#variant_name { #(#members: #bindings,)* });
+
+        let (ret_ty, ret_value) = match &variant.original.fields {
+            Fields::Unit => (quote!(()), quote!(())),
+            Fields::Unnamed(_) if variant.fields.len() == 1 => {
+                let ty = variant.fields[0].ty;
+                (quote!(&#ty), quote!(__0))
+            }
+            Fields::Unnamed(_) => {
+                let tys = variant.fields.iter().map(|f| f.ty);
+                (quote!((#(&#tys,)*)), quote!((#(#bindings,)*)))
+            }
+            Fields::Named(_) => {
+                let ref_struct = format_ident!("{}Ref", variant_name);
+                let names: Vec<_> = variant.fields.iter().map(|f| &f.member).collect();
+                let tys = variant.fields.iter().map(|f| f.ty);
+                let doc = format!(
+                    "The references to the fields of a [`{input_type}::{variant_name}`] variant."
+                );
+
+                ref_structs.push(quote!(
+                    #[doc = #doc]
+                    #[derive(Clone, Copy)]
+                    #vis struct #ref_struct<'a> {
+                        #(#vis #names: &'a #tys,)*
+                    }
+                ));
+
+                (
+                    quote!(#ref_struct<'_>),
+                    quote!(#ref_struct { #(#names: #bindings,)* }),
+                )
+            }
+        };
+
+        let as_doc = format!(
+            "Returns the references to the fields if the inner error is a \
+             [`{input_type}::{variant_name}`] variant, or `None` otherwise."
+        );
+        accessors.push(quote!(
+            #[doc = #as_doc]
+            #vis fn #as_name(&self) -> std::option::Option<#ret_ty> {
+                #[allow(unreachable_patterns)]
+                match self.inner() {
+                    #pattern => std::option::Option::Some(#ret_value),
+                    _ => std::option::Option::None,
+                }
+            }
+        ));
+    }
+
+    Ok((quote!(#(#accessors)*), quote!(#(#ref_structs)*)))
+}
+
 pub fn derive_ctor(input: &DeriveInput, t: DeriveCtorType) -> Result<TokenStream> {
     let input_type = input.ident.clone();
     let vis = &input.vis;
 
     let DeriveMeta { impl_type, .. } = resolve_meta(input)?;
 
+    // The new type carries the same generics as the input type.
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     let input = Input::from_syn(input)?;
 
-    let input = match input {
-        Input::Struct(input) => {
-            return Err(Error::new_spanned(
-                input.original,
-                "only `enum` is supported for `Construct` and `ContextInto`",
-            ))
+    // Whether `#[construct(builder)]` is specified on the type, for all variants.
+    let builder_for_all = match &input {
+        Input::Struct(_) => false,
+        Input::Enum(input) => input.attrs.construct_builder.is_some(),
+    };
+    // Whether to generate the lazy `into_*_with` methods by default.
+    let with_for_all = match &input {
+        Input::Struct(_) => true,
+        Input::Enum(input) => input.attrs.context_into_with.unwrap_or(true),
+    };
+    // The marker trait specified with `#[context_into(marker = "...")]` on the type.
+    let marker = match &input {
+        Input::Struct(input) => input.attrs.context_into_marker.clone(),
+        Input::Enum(input) => {
+            if let Some(marker) = input
+                .variants
+                .iter()
+                .find_map(|v| v.attrs.context_into_marker.as_ref())
+            {
+                return Err(Error::new_spanned(
+                    marker,
+                    "`marker` can only be specified on the type",
+                ));
+            }
+            input.attrs.context_into_marker.clone()
         }
-        Input::Enum(input) => input,
+    };
+    let marker_def = marker
+        .as_ref()
+        .filter(|_| matches!(t, DeriveCtorType::ContextInto))
+        .map(|marker| {
+            let doc = format!(
+                "Marker trait for the types convertible into the source errors of \
+                 [`{input_type}`], enabling the `into_*` methods of the extensions \
+                 derived by `ContextInto` on them.",
+            );
+            quote!(
+                #[doc = #doc]
+                #vis trait #marker {}
+            )
+        });
+    let marker = marker.as_ref();
+
+    let variants = match input {
+        Input::Struct(input) => vec![Either::Left(input)],
+        Input::Enum(input) => input.variants.into_iter().map(Either::Right).collect(),
     };
 
     let mut items = Vec::new();
+    let mut builders = Vec::new();
+    let mut ctor_names = HashSet::new();
 
-    for variant in input.variants {
+    for variant in variants {
         // Why not directly use `From`?
-        if variant.from_field().is_some() {
+        if for_both!(&variant, v => v.from_field()).is_some() {
             continue;
         }
 
+        let attrs = for_both!(&variant, v => &v.attrs);
         let skipped = match t {
-            DeriveCtorType::Construct => variant.attrs.construct_skip.is_some(),
-            DeriveCtorType::ContextInto => variant.attrs.context_into_skip.is_some(),
+            DeriveCtorType::Construct => attrs.construct_skip.is_some(),
+            DeriveCtorType::ContextInto => attrs.context_into_skip.is_some(),
         };
         if skipped {
             continue;
         }
 
-        let variant_name = &variant.ident;
+        let variant_name = for_both!(&variant, v => &v.ident);
+        let fields = for_both!(&variant, v => &v.fields);
+
+        let vis = match &attrs.construct_vis {
+            Some(construct_vis) if matches!(t, DeriveCtorType::Construct) => {
+                if visibility_rank(construct_vis) > visibility_rank(vis) {
+                    return Err(Error::new_spanned(
+                        construct_vis,
+                        "the visibility of the constructor should not be broader than the type",
+                    ));
+                }
+                construct_vis
+            }
+            _ => vis,
+        };
 
         let Args {
             other_args,
             other_names,
             other_tys,
             source_arg,
+            source_name,
             ctor_args,
+            no_source_ctor_args,
+            try_params,
         } = resolve_variant_args(
-            &variant,
+            fields,
             match t {
                 DeriveCtorType::Construct => SourceInto::Yes,
                 DeriveCtorType::ContextInto => SourceInto::No,
             },
+            matches!(t, DeriveCtorType::Construct) && attrs.construct_try.is_some(),
         );
 
-        let ctor_expr = quote!(#input_type::#variant_name {
+        let (ctor_path, target) = match &variant {
+            Either::Left(_s) => (quote!(#input_type), input_type.to_string()),
+            Either::Right(_v) => (
+                quote!(#input_type::#variant_name),
+                format!("{input_type}::{variant_name}"),
+            ),
+        };
+        let ctor_expr = quote!(#ctor_path {
             #(#ctor_args)*
         });
 
         let item = match t {
+            DeriveCtorType::Construct if builder_for_all || attrs.construct_builder.is_some() => {
+                if let Some(attr) = attrs.construct_try {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "`try` is not supported for builders",
+                    ));
+                }
+                if !generics.params.is_empty() {
+                    return Err(Error::new_spanned(
+                        generics,
+                        "`builder` is not supported for generic types",
+                    ));
+                }
+                let builder_name = format_ident!("{}Builder", variant_name);
+                builders.push(derive_builder(
+                    vis,
+                    &builder_name,
+                    &target,
+                    fields,
+                    &ctor_path,
+                    &impl_type,
+                ));
+                continue;
+            }
             DeriveCtorType::Construct => {
-                let ctor_name = format_ident!(
-                    "{}",
-                    big_camel_case_to_snake_case(&variant_name.to_string()),
-                    span = variant_name.span()
-                );
-                let doc = format!("Constructs a [`{input_type}::{variant_name}`] variant.");
+                if let Some(attr) = fields.iter().find_map(|f| f.attrs.construct_default) {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "`default` is only supported for builders",
+                    ));
+                }
+                let (auto_ctor_name, doc) = match &variant {
+                    Either::Left(_s) => (
+                        format_ident!("new", span = variant_name.span()),
+                        format!("Constructs a [`{input_type}`]."),
+                    ),
+                    Either::Right(_v) => (
+                        format_ident!(
+                            "{}",
+                            big_camel_case_to_snake_case(&variant_name.to_string()),
+                            span = variant_name.span()
+                        ),
+                        format!("Constructs a [`{input_type}::{variant_name}`] variant."),
+                    ),
+                };
+                let ctor_name = attrs.construct_name.clone().unwrap_or(auto_ctor_name);
+                let no_source_ctor_name = no_source_ctor_args
+                    .as_ref()
+                    .map(|_| format_ident!("{}_without_source", ctor_name.unraw()));
+
+                for name in std::iter::once(&ctor_name)
+                    .chain(&attrs.construct_aliases)
+                    .chain(&no_source_ctor_name)
+                {
+                    if !ctor_names.insert(name.to_string()) {
+                        return Err(Error::new_spanned(
+                            name,
+                            format!("duplicate constructor name `{name}`"),
+                        ));
+                    }
+                }
 
-                quote!(
-                    #[doc = #doc]
-                    #vis fn #ctor_name(#source_arg #(#other_args)*) -> Self {
-                        #ctor_expr.into()
+                // The signature of a fallible constructor, which is generic over the
+                // arguments converted with `TryInto`. The error type must be
+                // convertible from all of the conversion errors.
+                let (try_generics, ret, try_where, ok) = match attrs.construct_try {
+                    Some(_) => {
+                        let error_ty = match &attrs.construct_try_error {
+                            Some(error_ty) => quote!(#error_ty),
+                            None => quote!(
+                                std::boxed::Box<dyn std::error::Error + Send + Sync + 'static>
+                            ),
+                        };
+                        let (params, tys): (Vec<_>, Vec<_>) = try_params.iter().cloned().unzip();
+                        (
+                            quote!(<#(#params: std::convert::TryInto<#tys>),*>),
+                            quote!(std::result::Result<Self, #error_ty>),
+                            quote!(where #(#error_ty: std::convert::From<
+                                <#params as std::convert::TryInto<#tys>>::Error
+                            >),*),
+                            quote!(std::result::Result::Ok),
+                        )
                     }
+                    None => (quote!(), quote!(Self), quote!(), quote!()),
+                };
+
+                let call_args: Vec<_> = source_name.iter().chain(&other_names).collect();
+                let aliases = attrs.construct_aliases.iter().map(|alias| {
+                    let doc = format!("Alias of [`Self::{ctor_name}`].");
+                    quote!(
+                        #[doc = #doc]
+                        #vis fn #alias #try_generics(#source_arg #(#other_args)*) -> #ret #try_where {
+                            Self::#ctor_name(#(#call_args),*)
+                        }
+                    )
+                });
+
+                if let (Some(attr), Some(_)) = (attrs.construct_const, attrs.construct_try) {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "`const` constructor cannot be fallible",
+                    ));
+                }
+
+                let ctor = if let Some(attr) = attrs.construct_const {
+                    // `Into` conversions and capturing backtraces are not allowed in `const fn`.
+                    if source_arg.is_some()
+                        || !other_args.is_empty()
+                        || fields.iter().any(|f| f.is_backtrace())
+                    {
+                        return Err(Error::new_spanned(
+                            attr,
+                            "`const` constructor is only supported for variants without fields \
+                             that need conversion",
+                        ));
+                    }
+                    if impl_type != input_type {
+                        return Err(Error::new_spanned(
+                            attr,
+                            "`const` constructor is not supported for new types",
+                        ));
+                    }
+                    quote!(
+                        #[doc = #doc]
+                        #vis const fn #ctor_name() -> Self {
+                            #ctor_expr
+                        }
+                    )
+                } else {
+                    quote!(
+                        #[doc = #doc]
+                        #vis fn #ctor_name #try_generics(#source_arg #(#other_args)*) -> #ret #try_where {
+                            #ok(#ctor_expr.into())
+                        }
+                    )
+                };
+
+                let no_source_ctor = no_source_ctor_args.map(|no_source_ctor_args| {
+                    let doc = format!("{} The optional source is set to `None`.", doc);
+                    quote!(
+                        #[doc = #doc]
+                        #vis fn #no_source_ctor_name #try_generics(#(#other_args)*) -> #ret #try_where {
+                            #ok(#ctor_path {
+                                #(#no_source_ctor_args)*
+                            }
+                            .into())
+                        }
+                    )
+                });
+
+                quote!(
+                    #ctor
+                    #no_source_ctor
+                    #(#aliases)*
                 )
             }
             DeriveCtorType::ContextInto => {
@@ -415,25 +1452,45 @@ pub fn derive_ctor(input: &DeriveInput, t: DeriveCtorType) -> Result<TokenStream
                 if source_arg.is_none() || other_args.is_empty() {
                     continue;
                 }
-                let source_ty = variant.source_field().unwrap().ty;
+                let source_ty = for_both!(&variant, v => v.source_field()).unwrap().ty;
                 let source_ty_name = get_type_string(source_ty);
 
-                let ext_name = format_ident!("Into{}", variant_name, span = variant_name.span());
+                // The base of the method names, e.g., `parse_int` in `into_parse_int`.
+                let (method_base, method_span) = match &attrs.context_into_name {
+                    Some(name) => (name.unraw().to_string(), name.span()),
+                    None => (
+                        big_camel_case_to_snake_case(&variant_name.to_string()),
+                        variant_name.span(),
+                    ),
+                };
+
+                // The trait is named after the methods if renamed, e.g., `IntoOpenFile`
+                // for `into_open_file`.
+                let ext_name = match &attrs.context_into_name {
+                    Some(_) => format_ident!(
+                        "Into{}",
+                        snake_case_to_big_camel_case(&method_base),
+                        span = method_span
+                    ),
+                    None => format_ident!("Into{}", variant_name, span = variant_name.span()),
+                };
+                if !ctor_names.insert(method_base.clone()) {
+                    return Err(Error::new(
+                        method_span,
+                        format!("duplicate method name `into_{method_base}`"),
+                    ));
+                }
 
                 let doc_trait = format!(
                     "Extension trait for converting [`{source_ty_name}`] \
-                     into [`{input_type}::{variant_name}`] with the given context.",
+                     into [`{target}`] with the given context.",
                 );
 
                 let method_sig = {
-                    let name = format_ident!(
-                        "into_{}",
-                        big_camel_case_to_snake_case(&variant_name.to_string()),
-                        span = variant_name.span()
-                    );
+                    let name = format_ident!("into_{}", method_base, span = method_span);
                     let doc = format!(
                         "Converts [`{source_ty_name}`] \
-                         into [`{input_type}::{variant_name}`] with the given context.",
+                         into [`{target}`] with the given context.",
                     );
 
                     quote!(
@@ -442,27 +1499,23 @@ pub fn derive_ctor(input: &DeriveInput, t: DeriveCtorType) -> Result<TokenStream
                     )
                 };
 
+                let ret_tys: Vec<_> = other_names
+                    .iter()
+                    .map(|name| format_ident!("__{}", name.to_string().to_uppercase()))
+                    .collect();
+                let ret_ty_bounds: Vec<_> = ret_tys
+                    .iter()
+                    .zip(other_tys.iter())
+                    .map(|(ret_ty, ty)| quote!(#ret_ty: Into<#ty>))
+                    .collect();
+
                 let method_with_sig = {
-                    let name = format_ident!(
-                        "into_{}_with",
-                        big_camel_case_to_snake_case(&variant_name.to_string()),
-                        span = variant_name.span()
-                    );
+                    let name = format_ident!("into_{}_with", method_base, span = method_span);
                     let doc = format!(
                         "Converts [`{source_ty_name}`] \
-                         into [`{input_type}::{variant_name}`] with the context returned by the given function.",
+                         into [`{target}`] with the context returned by the given function.",
                     );
 
-                    let ret_tys: Vec<_> = other_names
-                        .iter()
-                        .map(|name| format_ident!("__{}", name.to_string().to_uppercase()))
-                        .collect();
-                    let ret_ty_bounds: Vec<_> = ret_tys
-                        .iter()
-                        .zip(other_tys.iter())
-                        .map(|(ret_ty, ty)| quote!(#ret_ty: Into<#ty>))
-                        .collect();
-
                     quote!(
                         #[doc = #doc]
                         fn #name<__F, #( #ret_tys, )*>(
@@ -475,34 +1528,150 @@ pub fn derive_ctor(input: &DeriveInput, t: DeriveCtorType) -> Result<TokenStream
                     )
                 };
 
+                let method_with_source_sig = {
+                    let name =
+                        format_ident!("into_{}_with_source", method_base, span = method_span);
+                    let doc = format!(
+                        "Converts [`{source_ty_name}`] \
+                         into [`{target}`] with the context returned by the given function, \
+                         which takes the reference to the source error.",
+                    );
+
+                    quote!(
+                        #[doc = #doc]
+                        fn #name<__F, #( #ret_tys, )*>(
+                            self,
+                            f: __F,
+                        ) -> Self::Ret
+                        where
+                            __F: FnOnce(&#source_ty) -> (#( #ret_tys ),*),
+                            #( #ret_ty_bounds, )*
+                    )
+                };
+
+                // Implement for the source error itself and `Result<T, SourceError>`. If the
+                // source is optional, i.e., `Option<SourceError>`, implement for the inner type
+                // instead with the source wrapped in `Some`, and also for the `Option` itself.
+                let (source_inner_ty, wrap) = match type_parameter_of_option(source_ty) {
+                    Some(inner_ty) => (inner_ty, quote!(std::option::Option::Some)),
+                    None => (source_ty, quote!()),
+                };
+                // Drop the lazy methods if `no_with` is specified.
+                let with = attrs.context_into_with.unwrap_or(with_for_all);
+                let (method_with_sig, method_with_source_sig) = if with {
+                    (Some(method_with_sig), Some(method_with_source_sig))
+                } else {
+                    (None, None)
+                };
+                // Implement with the given impl header, where `source` is the expression
+                // converting `self` into the source field.
+                let impl_for = |header: TokenStream, source: TokenStream| {
+                    let method_with = method_with_sig.as_ref().map(|method_with_sig| {
+                        quote!(
+                            #method_with_sig {
+                                let (#( #other_names ),*) = f();
+                                let __source: #source_ty = #source;
+                                (move |#source_arg| #ctor_expr.into())(__source)
+                            }
+                        )
+                    });
+                    let method_with_source =
+                        method_with_source_sig
+                            .as_ref()
+                            .map(|method_with_source_sig| {
+                                quote!(
+                                    #method_with_source_sig {
+                                        let __source: #source_ty = #source;
+                                        let (#( #other_names ),*) = f(&__source);
+                                        (move |#source_arg| #ctor_expr.into())(__source)
+                                    }
+                                )
+                            });
+
+                    quote!(
+                        #header {
+                            type Ret = #impl_type;
+                            #method_sig {
+                                let __source: #source_ty = #source;
+                                (move |#source_arg| #ctor_expr.into())(__source)
+                            }
+                            #method_with
+                            #method_with_source
+                        }
+                    )
+                };
+                let impl_for_source = impl_for(
+                    quote!(impl #ext_name for #source_inner_ty),
+                    quote!(#wrap(self)),
+                );
+                let impl_for_option = (!wrap.is_empty())
+                    .then(|| impl_for(quote!(impl #ext_name for #source_ty), quote!(self)));
+                // Also implement for the types convertible into the source if they opt in
+                // with the marker trait, which keeps it from overlapping with the other impls.
+                let impl_for_marked = marker.map(|marker| {
+                    impl_for(
+                        quote!(
+                            impl<__E> #ext_name for __E
+                            where
+                                __E: #marker + Into<#source_inner_ty>,
+                        ),
+                        quote!(#wrap(self.into())),
+                    )
+                });
+
+                let result_method_with = method_with_sig.as_ref().map(|method_with_sig| {
+                    quote!(
+                        #method_with_sig {
+                            self.map_err(move |__source| {
+                                let (#( #other_names ),*) = f();
+                                let __source: #source_ty = #wrap(__source.into());
+                                (move |#source_arg| #ctor_expr.into())(__source)
+                            })
+                        }
+                    )
+                });
+                let result_method_with_source =
+                    method_with_source_sig
+                        .as_ref()
+                        .map(|method_with_source_sig| {
+                            quote!(
+                                #method_with_source_sig {
+                                    self.map_err(move |__source| {
+                                        let __source: #source_ty = #wrap(__source.into());
+                                        let (#( #other_names ),*) = f(&__source);
+                                        (move |#source_arg| #ctor_expr.into())(__source)
+                                    })
+                                }
+                            )
+                        });
+
+                let method_with_sig = method_with_sig.map(|sig| quote!(#sig;));
+                let method_with_source_sig = method_with_source_sig.map(|sig| quote!(#sig;));
+
                 quote!(
                     #[doc = #doc_trait]
                     #vis trait #ext_name {
                         type Ret;
                         #method_sig;
-                        #method_with_sig;
+                        #method_with_sig
+                        #method_with_source_sig
                     }
-                    impl #ext_name for #source_ty {
-                        type Ret = #impl_type;
-                        #method_sig {
-                            (move |#source_arg| #ctor_expr.into())(self)
-                        }
-                        #method_with_sig {
-                            let (#( #other_names ),*) = f();
-                            (move |#source_arg| #ctor_expr.into())(self)
-                        }
-                    }
-                    impl<__T> #ext_name for std::result::Result<__T, #source_ty> {
+                    #impl_for_source
+                    #impl_for_option
+                    #impl_for_marked
+                    impl<__T, __E> #ext_name for std::result::Result<__T, __E>
+                    where
+                        __E: Into<#source_inner_ty>,
+                    {
                         type Ret = std::result::Result<__T, #impl_type>;
                         #method_sig {
-                            self.map_err(move |#source_arg| #ctor_expr.into())
-                        }
-                        #method_with_sig {
-                            self.map_err(move |#source_arg| {
-                                let (#( #other_names ),*) = f();
-                                #ctor_expr.into()
+                            self.map_err(move |__source| {
+                                let __source: #source_ty = #wrap(__source.into());
+                                (move |#source_arg| #ctor_expr.into())(__source)
                             })
                         }
+                        #result_method_with
+                        #result_method_with_source
                     }
                 )
             }
@@ -515,25 +1684,158 @@ pub fn derive_ctor(input: &DeriveInput, t: DeriveCtorType) -> Result<TokenStream
         DeriveCtorType::Construct => {
             quote!(
                 #[automatically_derived]
-                impl #impl_type {
+                impl #impl_generics #impl_type #ty_generics #where_clause {
                     #(#items)*
                 }
+
+                #(#builders)*
             )
         }
         DeriveCtorType::ContextInto => {
-            quote!(#(#items)*)
+            quote!(
+                #marker_def
+                #(#items)*
+            )
         }
     };
 
     Ok(generated)
 }
 
-pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream> {
+/// Generates a builder for the struct or the variant, where the fields of
+/// `Option` types or with `#[construct(default)]` are optional and set with
+/// setters, while the others are required in `new`.
+fn derive_builder(
+    vis: &Visibility,
+    builder_name: &Ident,
+    target: &str,
+    fields: &[Field<'_>],
+    ctor_path: &TokenStream,
+    impl_type: &Ident,
+) -> TokenStream {
+    let mut builder_fields = Vec::new();
+    let mut new_args = Vec::new();
+    let mut new_inits = Vec::new();
+    let mut setters = Vec::new();
+    let mut ctor_args = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let ty = &field.ty;
+        let member = &field.member;
+
+        let name = match &field.member {
+            Member::Named(named) => named.clone(),
+            Member::Unnamed(_) => {
+                if field.is_non_from_source() {
+                    format_ident!("source")
+                } else {
+                    format_ident!("arg_{}", i)
+                }
+            }
+        };
+
+        if field.is_backtrace() {
+            let expr = if type_is_option(ty) {
+                quote!(std::option::Option::Some(
+                    std::backtrace::Backtrace::capture()
+                ))
+            } else {
+                quote!(std::convert::From::from(
+                    std::backtrace::Backtrace::capture()
+                ))
+            };
+            ctor_args.push(quote!(#member: #expr,));
+            continue;
+        }
+
+        builder_fields.push(quote!(#name: #ty,));
+        ctor_args.push(quote!(#member: self.#name,));
+
+        let doc = format!("Sets the `{name}` field.");
+        match type_parameter_of_option(ty) {
+            _ if field.attrs.construct_default.is_some() => {
+                let arg_ty = ctor_arg_ty(field, ty);
+                new_inits.push(quote!(#name: std::default::Default::default(),));
+                setters.push(quote!(
+                    #[doc = #doc]
+                    #vis fn #name(mut self, #name: #arg_ty) -> Self {
+                        self.#name = #name.into();
+                        self
+                    }
+                ));
+            }
+            Some(inner_ty) if !field.is_non_from_source() => {
+                let arg_ty = ctor_arg_ty(field, inner_ty);
+                new_inits.push(quote!(#name: std::option::Option::None,));
+                setters.push(quote!(
+                    #[doc = #doc]
+                    #vis fn #name(mut self, #name: #arg_ty) -> Self {
+                        self.#name = std::option::Option::Some(#name.into());
+                        self
+                    }
+                ));
+            }
+            _ => {
+                let arg_ty = ctor_arg_ty(field, ty);
+                new_args.push(quote!(#name: #arg_ty,));
+                new_inits.push(quote!(#name: #name.into(),));
+            }
+        }
+    }
+
+    let doc = format!("A builder for [`{target}`].");
+    let doc_new = format!("Creates a builder for [`{target}`] with the required fields.");
+    let doc_build = format!("Builds the [`{target}`].");
+
+    quote!(
+        #[doc = #doc]
+        #vis struct #builder_name {
+            #(#builder_fields)*
+        }
+
+        #[automatically_derived]
+        impl #builder_name {
+            #[doc = #doc_new]
+            #vis fn new(#(#new_args)*) -> Self {
+                Self {
+                    #(#new_inits)*
+                }
+            }
+
+            #(#setters)*
+
+            #[doc = #doc_build]
+            #vis fn build(self) -> #impl_type {
+                #ctor_path {
+                    #(#ctor_args)*
+                }
+                .into()
+            }
+        }
+    )
+}
+
+#[derive(Clone, PartialEq)]
+enum MacroType {
+    /// Constructs the error.
+    Ctor,
+    /// Constructs the error and bails out.
+    Bail,
+    /// Constructs the error and bails out if the condition is false.
+    Ensure,
+    /// Constructs the error and logs it with `tracing` at the given level.
+    Log(Ident),
+}
+
+fn derive_macro_inner(input: &DeriveInput, t: MacroType) -> Result<TokenStream> {
     let DeriveMeta {
         impl_type,
         macro_mangle,
+        macro_location,
+        macro_prefix,
         macro_path,
         macro_vis,
+        macro_doc,
         ..
     } = resolve_meta(input)?;
 
@@ -549,9 +1851,65 @@ pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream
     let mut items = Vec::new();
 
     for variant in variants {
+        let variant_meta = match &variant {
+            Either::Left(_s) => VariantMacroMeta::default(),
+            Either::Right(v) => resolve_variant_macro_meta(v.original)?,
+        };
+        if variant_meta.skip {
+            continue;
+        }
+
         // We only care about variants with `message` field.
-        if for_both!(&variant, v => v.message_field()).is_none() {
+        let Some(message_field) = for_both!(&variant, v => v.message_field()) else {
+            // This is likely a mistake like a misspelled field name, so warn about
+            // it unless explicitly skipped. There's no way to emit a warning from a
+            // proc-macro on stable, so use a deprecated item instead.
+            if let MacroType::Ctor = t {
+                let ident = for_both!(&variant, v => &v.ident);
+                items.push(quote_spanned!(ident.span()=>
+                    const _: () = {
+                        #[deprecated(note = "no `message` field found, so no macro is generated; \
+                                             name a field `message` or mark it with `#[message]`, \
+                                             or specify `#[thiserror_ext(macro(skip))]` to skip it")]
+                        const MISSING_MESSAGE_FIELD: () = ();
+                        MISSING_MESSAGE_FIELD
+                    };
+                ));
+            }
             continue;
+        };
+
+        // The `#[location]` field is filled by the macros only if opted in.
+        match (macro_location, for_both!(&variant, v => v.location_field())) {
+            (Some(span), None) => {
+                let ident = for_both!(&variant, v => &v.ident);
+                let mut error = Error::new(
+                    ident.span(),
+                    "no `#[location]` field found, which is required by `location`",
+                );
+                error.combine(Error::new(span, "`location` is specified here"));
+                return Err(error);
+            }
+            (None, Some(field)) => {
+                return Err(Error::new_spanned(
+                    field.attrs.location.unwrap(),
+                    "specify `#[thiserror_ext(macro(location))]` to fill the `#[location]` field in the macros",
+                ));
+            }
+            _ => {}
+        }
+
+        // The message is constructed with `format!(..).into()`. Assert the bound
+        // with the span of the field type for a helpful error message, only once
+        // for all kinds of macros.
+        if let MacroType::Ctor = t {
+            let message_ty = message_field.ty;
+            items.push(quote_spanned!(message_ty.span()=>
+                const _: fn() = || {
+                    fn assert_message_from_string<T: std::convert::From<std::string::String>>() {}
+                    assert_message_from_string::<#message_ty>();
+                };
+            ));
         }
 
         let variant_name = match &variant {
@@ -569,19 +1927,21 @@ pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream
             other_args,
             other_call_args,
             ctor_args,
-        } = resolve_args_for_macro(fields);
-
-        let ctor_expr = quote!(#ctor_path {
-            #(#ctor_args)*
-        });
-
-        let bail_prefix = if bail { "bail_" } else { "" };
-        let bail_suffix = if bail { "__bail" } else { "" };
+            literal_ctor_args,
+        } = resolve_args_for_macro(fields, message_field);
+
+        let (bail_prefix, bail_suffix) = match &t {
+            MacroType::Ctor => ("", ""),
+            MacroType::Bail => ("bail_", "__bail"),
+            MacroType::Ensure => ("ensure_", "__ensure"),
+            MacroType::Log(_) => ("log_", "__log"),
+        };
 
         let ctor_span = for_both!(&variant, v => v.ident.span());
 
         let export_name = format_ident!(
-            "{}{}",
+            "{}{}{}",
+            macro_prefix,
             bail_prefix,
             big_camel_case_to_snake_case(&variant_name.to_string()),
             span = ctor_span,
@@ -598,7 +1958,19 @@ pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream
             export_name.clone()
         };
 
-        let bail_doc = if bail { " and bails out" } else { "" };
+        let extra_doc = variant_meta.doc.or_else(|| macro_doc.clone()).map(|doc| {
+            quote!(
+                #[doc = ""]
+                #[doc = #doc]
+            )
+        });
+
+        let bail_doc = match &t {
+            MacroType::Ctor => "",
+            MacroType::Bail => " and bails out",
+            MacroType::Ensure => " and bails out if the condition is false",
+            MacroType::Log(_) => " and logs it with `tracing`",
+        };
         let doc = match &variant {
             Either::Left(_s) => {
                 format!("Constructs a [`{input_type}`]{bail_doc}.")
@@ -615,6 +1987,12 @@ pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream
         let message_arg = quote!($($fmt_arg:tt)*);
         let message_call_arg = quote!($($fmt_arg)*);
 
+        // The condition is passed through as the first argument for `ensure`.
+        let (cond_arg, cond_call_arg) = match &t {
+            MacroType::Ensure => (quote!($cond:expr,), quote!($cond,)),
+            MacroType::Ctor | MacroType::Bail | MacroType::Log(_) => (quote!(), quote!()),
+        };
+
         for bitset in (0..(1 << len)).rev() {
             let mut args = Vec::new();
             let mut call_args = Vec::new();
@@ -629,30 +2007,77 @@ pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream
             }
 
             let arm = quote!(
-                (#(#args)* #message_arg) => {
-                    #export_name!(@ #(#call_args)* #message_call_arg)
+                (#cond_arg #(#args)* #message_arg) => {
+                    #export_name!(@ #cond_call_arg #(#call_args)* #message_call_arg)
                 };
             );
             arms.push(arm);
         }
 
-        let full_inner = if bail {
-            quote!({
-                let res: #macro_path #impl_type = (#ctor_expr).into();
-                return ::std::result::Result::Err(res.into());
-            })
-        } else {
-            quote!({
-                let res: #macro_path #impl_type = (#ctor_expr).into();
-                res
-            })
+        let full_inner = |ctor_args: &[TokenStream]| {
+            let ctor_expr = quote!(#ctor_path {
+                #(#ctor_args)*
+            });
+
+            match &t {
+                MacroType::Ctor => quote!({
+                    let res: #macro_path #impl_type = (#ctor_expr).into();
+                    res
+                }),
+                MacroType::Bail => quote!({
+                    let res: #macro_path #impl_type = (#ctor_expr).into();
+                    return ::std::result::Result::Err(res.into());
+                }),
+                MacroType::Ensure => quote!({
+                    if !($cond) {
+                        let res: #macro_path #impl_type = (#ctor_expr).into();
+                        return ::std::result::Result::Err(res.into());
+                    }
+                }),
+                MacroType::Log(level) => quote!({
+                    let res: #macro_path #impl_type = (#ctor_expr).into();
+                    {
+                        use ::thiserror_ext::AsReport;
+                        ::thiserror_ext::__private::tracing::event!(
+                            ::thiserror_ext::__private::tracing::Level::#level,
+                            error = %res.as_report(),
+                        );
+                    }
+                    res
+                }),
+            }
+        };
+        let literal_full = literal_ctor_args.map(|literal_ctor_args| {
+            let literal_full_inner = full_inner(&literal_ctor_args);
+            quote!(
+                (@ #cond_arg #(#other_args)* $message:tt) => {
+                    #literal_full_inner
+                };
+            )
+        });
+        let full_inner = full_inner(&ctor_args);
+
+        let empty = match &t {
+            MacroType::Ctor | MacroType::Bail | MacroType::Log(_) => quote!(
+                () => { // empty macro call
+                    #export_name!("")
+                };
+            ),
+            MacroType::Ensure => quote!(
+                ($cond:expr $(,)?) => { // no message specified
+                    #export_name!(
+                        $cond,
+                        "{}",
+                        ::std::concat!("condition failed: `", ::std::stringify!($cond), "`")
+                    )
+                };
+            ),
         };
 
         let full = quote!(
-            () => { // empty macro call
-                #export_name!("")
-            };
-            (@ #(#other_args)* #message_arg) => {
+            #empty
+            #literal_full
+            (@ #cond_arg #(#other_args)* #message_arg) => {
                 #full_inner
             };
         );
@@ -665,6 +2090,7 @@ pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream
 
         let item = quote!(
             #[doc = #doc]
+            #extra_doc
             #[allow(unused_macros)]
             #macro_export
             macro_rules! #mangled_name {
@@ -687,12 +2113,32 @@ pub fn derive_macro_inner(input: &DeriveInput, bail: bool) -> Result<TokenStream
 }
 
 pub fn derive_macro(input: &DeriveInput) -> Result<TokenStream> {
-    let ctor = derive_macro_inner(input, false)?;
-    let bail = derive_macro_inner(input, true)?;
+    let DeriveMeta {
+        macro_no_bail,
+        macro_log,
+        ..
+    } = resolve_meta(input)?;
+
+    let ctor = derive_macro_inner(input, MacroType::Ctor)?;
+    // Both `bail_*` and `ensure_*` return early from the function.
+    let (bail, ensure) = if macro_no_bail {
+        (quote!(), quote!())
+    } else {
+        (
+            derive_macro_inner(input, MacroType::Bail)?,
+            derive_macro_inner(input, MacroType::Ensure)?,
+        )
+    };
+    let log = match macro_log {
+        Some(level) => derive_macro_inner(input, MacroType::Log(level))?,
+        None => quote!(),
+    };
 
     let generated = quote!(
         #ctor
         #bail
+        #ensure
+        #log
     );
 
     Ok(generated)
@@ -716,6 +2162,57 @@ pub fn derive_report_debug(input: &DeriveInput) -> Result<TokenStream> {
     Ok(generated)
 }
 
+pub fn derive_report_display(input: &DeriveInput) -> Result<TokenStream> {
+    let input_type = input.ident.clone();
+
+    // Delegating to the report of `self` would recurse infinitely, as the report
+    // formats the error itself with `Display`. So only allow wrapping an error.
+    let field = match &input.data {
+        Data::Struct(data) if data.fields.len() == 1 => data.fields.iter().next().unwrap(),
+        _ => return Err(Error::new_spanned(
+            input,
+            "`ReportDisplay` can only be derived for structs with a single field wrapping an error",
+        )),
+    };
+    if let Some(attr) = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("error"))
+    {
+        return Err(Error::new_spanned(
+            attr,
+            "`ReportDisplay` conflicts with the `Display` implementation generated by `#[error(..)]`",
+        ));
+    }
+
+    let member = match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(0.into()),
+    };
+
+    // Passthrough the `alternate` flag.
+    let generated = quote!(
+        impl ::std::fmt::Display for #input_type {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                use ::thiserror_ext::AsReport;
+                ::std::fmt::Display::fmt(&self.#member.as_report(), f)
+            }
+        }
+    );
+
+    Ok(generated)
+}
+
+/// Returns the where clause of the generics with the extra predicates appended.
+fn where_clause_with(
+    generics: &Generics,
+    extra: impl IntoIterator<Item = TokenStream>,
+) -> TokenStream {
+    let existing = generics.where_clause.iter().flat_map(|w| &w.predicates);
+    let extra = extra.into_iter();
+    quote!(where #(#existing,)* #(#extra,)*)
+}
+
 fn big_camel_case_to_snake_case(input: &str) -> String {
     let mut output = String::new();
 
@@ -733,6 +2230,43 @@ fn big_camel_case_to_snake_case(input: &str) -> String {
     output
 }
 
+fn snake_case_to_big_camel_case(input: &str) -> String {
+    let mut output = String::new();
+    let mut upper = true;
+
+    for c in input.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            output.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Returns a rank of the visibility for comparing how broad it is. Paths in
+/// `pub(in ..)` are not resolved and considered as broad as `pub(super)`.
+fn visibility_rank(vis: &Visibility) -> u8 {
+    match vis {
+        Visibility::Public(_) => 3,
+        Visibility::Restricted(r) if r.path.is_ident("crate") => 2,
+        Visibility::Restricted(r) if r.path.is_ident("self") => 0,
+        Visibility::Restricted(_) => 1,
+        Visibility::Inherited => 0,
+    }
+}
+
+fn type_is_cow(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => ty.path.segments.last().unwrap().ident == "Cow",
+        _ => false,
+    }
+}
+
 fn type_is_option(ty: &Type) -> bool {
     type_parameter_of_option(ty).is_some()
 }
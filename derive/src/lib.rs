@@ -26,10 +26,115 @@ mod thiserror;
 ///     #[error("internal error: {0}")]
 ///     #[construct(skip)] // to skip generating the constructor
 ///     InternalError(String),
+///
+///     #[error("io error")]
+///     #[construct(name = "io")] // to rename the constructor from `io_error`
+///     IoError(#[source] std::io::Error),
 /// }
 ///
 /// // Any type that implements `Into<String>` is accepted as the argument.
 /// let _: Error = Error::unsupported_feature("foo");
+/// let _: Error = Error::io(io_error);
+/// ```
+///
+/// Additional names can be given with `#[construct(alias = "...")]`, which can
+/// be specified multiple times. A compile error is emitted if multiple
+/// constructors end up with the same name.
+///
+/// Specify `#[construct(const)]` on a variant without fields that need
+/// conversion, e.g., a unit variant, to make the constructor a `const fn`, so
+/// that it can be used to define error constants.
+///
+/// The constructors inherit the visibility of the type by default. Specify
+/// `#[construct(vis = pub(crate))]` on a variant to restrict the visibility of
+/// its constructor, which cannot be broader than the type.
+///
+/// Specify `#[construct(into = false)]` on a field to accept the exact type of
+/// the field instead of `impl Into<T>`, in case the conversion makes the type
+/// of the argument ambiguous at call sites, e.g., `"foo".into()` or
+/// `Default::default()`. This applies to the builders as well.
+///
+/// # Fallible constructors
+///
+/// Specify `#[construct(try)]` on a variant to validate the arguments at
+/// construction, e.g., for a field of `NonZeroU32`. The arguments other than
+/// the source are converted with [`TryInto`] instead, and the constructor
+/// returns `Result<Self, Box<dyn Error + Send + Sync>>` with the first
+/// conversion error.
+///
+/// Specify the error type with `#[construct(try = MyError)]` instead, which
+/// must implement [`From`] for all the conversion errors. Fields with
+/// `#[construct(into = false)]` are not converted and thus put no requirement
+/// on the error type.
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// enum Error {
+///     #[error("invalid port {port} of `{host}`")]
+///     #[construct(try)]
+///     InvalidPort { host: String, port: NonZeroU16 },
+/// }
+///
+/// let _: Error = Error::invalid_port("localhost", 8080u16)?;
+/// ```
+///
+/// This is not supported for builders or `const` constructors.
+///
+/// # Optional source
+///
+/// If the source field is of type `Option<E>`, the constructor accepts
+/// `impl Into<E>` and wraps it in `Some`. An extra constructor with the suffix
+/// `_without_source`, e.g., `parse_without_source`, is generated to construct
+/// the error without a source.
+///
+/// # Struct
+///
+/// For a `struct` error type, a single constructor named `new` is generated
+/// with the same argument semantics. It can also be renamed with
+/// `#[construct(name = "...")]` on the struct.
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// #[error("failed to read `{path}`")]
+/// struct ReadError {
+///     path: String,
+///     source: std::io::Error,
+/// }
+///
+/// let _: ReadError = ReadError::new(io_error, "foo.txt");
+/// ```
+///
+/// # Builder
+///
+/// For variants with many optional fields, specify `#[construct(builder)]` on
+/// the variant, or on the type for all variants, to generate a builder named
+/// `{Variant}Builder` instead of the constructor. Fields of `Option` types are
+/// optional and can be set with the setters, while the others are required in
+/// `new`. The setters take and return the builder by value, so that they can
+/// be chained until `build`.
+///
+/// Whether a type implements `Default` is unknown to the macro, so other
+/// optional fields must be marked with `#[construct(default)]`, which makes
+/// them default to `Default::default()` unless set.
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// enum Error {
+///     #[error("failed to request `{url}`")]
+///     #[construct(builder)]
+///     Request {
+///         url: String,
+///         method: Option<String>,
+///         status: Option<u16>,
+///         #[construct(default)]
+///         headers: Vec<String>,
+///     },
+/// }
+///
+/// let _: Error = RequestBuilder::new("https://example.com")
+///     .method("GET")
+///     .status(404u16)
+///     .build();
 /// ```
 ///
 /// # New type
@@ -85,13 +190,77 @@ pub fn derive_construct(input: TokenStream) -> TokenStream {
 /// // Specify the `from` as "foo" and convert it into `Error::ParseInt`.
 /// let _: Error = "foo".parse::<i32>().unwrap_err().into_parse_int("foo");
 ///
-/// // Can also be called on `Result<T, ExternalError>`
+/// // Can also be called on `Result<T, E>`, where `E` can be converted into
+/// // the external error type.
 /// let _: Result<i32, Error> = "foo".parse().into_parse_int("foo");
 ///
 /// // Call `into_*_with` with a closure to lazily evaluate the context.
 /// let _: Result<i32, Error> = "foo".parse().into_parse_int_with(|| format!("{}", 1 + 1));
+///
+/// // Call `into_*_with_source` to build the context from the source error.
+/// let _: Result<i32, Error> = "foo"
+///     .parse()
+///     .into_parse_int_with_source(|e| format!("{:?}", e.kind()));
 /// ```
 ///
+/// # Lazy methods
+///
+/// Specify `#[context_into(no_with)]` on a variant to skip generating the lazy
+/// methods `into_*_with` and `into_*_with_source`. When specified on the type,
+/// it applies to all variants, unless overridden by `#[context_into(with)]`
+/// on a variant.
+///
+/// # Method names
+///
+/// The methods are named after the variant by default. Specify
+/// `#[context_into(name = "...")]` on a variant to override it, e.g., with
+/// `name = "open_file"`, the methods are named `into_open_file`,
+/// `into_open_file_with` and so on, and the extension trait is named
+/// `IntoOpenFile` instead of `Into{Variant}`. A compile error is emitted if
+/// multiple variants end up with the same method names.
+///
+/// # Wrapper types
+///
+/// The extension trait is implemented for the source error type and results of
+/// errors convertible into it. To also call the methods directly on other error
+/// types convertible into the source, e.g., wrappers of it, specify
+/// `#[context_into(marker = "...")]` on the type. This generates a marker
+/// trait with the given name, and the extension traits are implemented for all
+/// types that implement both the marker trait and `Into<SourceError>`.
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, thiserror_ext::ContextInto)]
+/// #[context_into(marker = "ErrorSource")]
+/// enum Error {
+///     #[error("cannot parse int from `{from}`")]
+///     ParseInt {
+///         source: std::num::ParseIntError,
+///         from: String,
+///     },
+/// }
+///
+/// struct Wrapper(std::num::ParseIntError);
+/// impl From<Wrapper> for std::num::ParseIntError { /* ... */ }
+/// impl ErrorSource for Wrapper {}
+///
+/// let _: Error = Wrapper(e).into_parse_int("foo");
+/// ```
+///
+/// A blanket implementation for all `E: Into<SourceError>` would overlap with
+/// the ones for the source error and `Result`, hence the opt-in. So the marker
+/// trait must not be implemented for the source error types or `Result`.
+///
+/// # Optional source
+///
+/// If the source field is of type `Option<E>`, the extension trait is
+/// implemented for `E` and `Result<T, E>` with the source wrapped in `Some`,
+/// as well as for `Option<E>` itself in case there's no source.
+///
+/// # Struct
+///
+/// For a `struct` error type, the extension trait and methods are named after
+/// the struct instead, e.g., `IntoParseIntError` and `into_parse_int_error`.
+///
 /// # New type
 ///
 /// If a new type is specified with `#[thiserror_ext(newtype(..))]`, the
@@ -112,7 +281,7 @@ pub fn derive_context_into(input: TokenStream) -> TokenStream {
 }
 
 /// Generates macros for different variants of the error type to construct
-/// it or directly bail out.
+/// it, directly bail out, or bail out if a condition is not met.
 ///
 /// # Inline formatting
 ///
@@ -121,8 +290,16 @@ pub fn derive_context_into(input: TokenStream) -> TokenStream {
 /// [`format!`].
 ///
 /// To mark a field as the message to be formatted, name it `message` or mark
-/// it with `#[message]`. The message field can be any type that implements
-/// `From<String>`.
+/// it with `#[message]`. If any field is marked with `#[message]`, it takes
+/// precedence, and a field named `message` is treated as a regular field. The
+/// message field can be any type that implements `From<String>`, which is
+/// checked at compile time with an error pointing to the field type. If it's a
+/// `Cow<'static, str>` and the message is a single string literal without
+/// arguments, it will be borrowed without allocation.
+///
+/// No macros are generated for variants without a message field. As this is
+/// likely a mistake like a misspelled field name, a warning is emitted unless
+/// the variant is explicitly skipped with `#[thiserror_ext(macro(skip))]`.
 ///
 /// ## Example
 ///
@@ -138,6 +315,12 @@ pub fn derive_context_into(input: TokenStream) -> TokenStream {
 ///
 /// // Equivalent to `return Err(Error::Internal { msg: format!(..).into() }.into())`.
 /// bail_internal!("{} is a bad number", 42);
+///
+/// // Equivalent to `if !(cond) { bail_internal!(..) }`.
+/// ensure_internal!(n != 42, "{} is a bad number", n);
+///
+/// // The message defaults to "condition failed: `n != 42`" if not specified.
+/// ensure_internal!(n != 42);
 /// ```
 ///
 /// # Extra fields
@@ -183,6 +366,24 @@ pub fn derive_context_into(input: TokenStream) -> TokenStream {
 ///   types in the generated macros will use the qualified path like
 ///   `$crate::foo::bar::Error`, enabling the callers to use the macros without
 ///   importing the error type.
+/// - `doc = ".."`: extra documentation appended to the generated macros,
+///   which is helpful for exported macros. It can also be specified on a
+///   variant with `#[thiserror_ext(macro(doc = ".."))]` to override the one on
+///   the type.
+/// - `no_bail`: do not generate the `bail_*` and `ensure_*` macros, which
+///   return early from the function, for libraries that only need to
+///   construct the errors.
+/// - `log` or `log = ".."`: also generate the `log_*` macros that construct the
+///   error and log its report with `tracing` at the given level, one of
+///   `trace`, `debug`, `info`, `warn` or `error` (default). Requires the
+///   `tracing` feature.
+/// - `location`: fill the field marked with `#[location]` with the location of
+///   the macro call, i.e., [`std::panic::Location::caller`]. The field can be
+///   either `&'static Location<'static>` or `Location<'static>`.
+/// - `prefix = ".."`: prepend the prefix to the names of all generated macros,
+///   e.g., `mk_foo!` and `mk_bail_foo!` with `prefix = "mk_"`, so that the
+///   macros of multiple error types in the same module don't conflict. Unlike
+///   `mangle`, the names are still readable.
 ///
 /// # New type
 ///
@@ -194,7 +395,7 @@ pub fn derive_context_into(input: TokenStream) -> TokenStream {
 ///
 /// [`thiserror_ext::Box`]: derive@Box
 /// [`thiserror_ext::Arc`]: derive@Arc
-#[proc_macro_derive(Macro, attributes(thiserror_ext, message))]
+#[proc_macro_derive(Macro, attributes(thiserror_ext, message, location))]
 pub fn derive_macro(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -244,6 +445,42 @@ pub fn derive_macro(input: TokenStream) -> TokenStream {
 /// let _: ErrorKind = error.into_inner();
 /// ```
 ///
+/// Note that `into_inner` is only available for [`derive@Box`].
+///
+/// Following the convention of [`std::io::Error::kind`], specify
+/// `#[thiserror_ext(newtype(.., kind_alias))]` to also generate `kind` and
+/// `into_kind` as aliases of `inner` and `into_inner`.
+///
+/// For quick dispatch like categorization in metrics, specify
+/// `#[thiserror_ext(newtype(.., kind))]` to generate a `#[non_exhaustive]`
+/// fieldless enum mirroring the variants of the original error type, named
+/// `{NewType}Kind` by default or specified with `kind = Name`. In this case,
+/// `kind` returns the variant of this enum instead. As both generate `kind`,
+/// specifying `kind_alias` together with `kind` is a compile error.
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = Error, kind = ErrorCategory))]
+/// enum ErrorKind {
+///     #[error("foo")]
+///     Foo,
+/// }
+///
+/// let error: Error = ErrorKind::Foo.into();
+/// assert_eq!(error.kind(), ErrorCategory::Foo);
+/// ```
+///
+/// To look for an error of a specific type, specify
+/// `#[thiserror_ext(newtype(.., downcast))]` to generate `downcast_ref`.
+/// Besides the sources, it also checks the fields of the inner error, so that
+/// errors wrapped with `#[error(transparent)]` can be found as well. This
+/// requires all fields of the inner error to be `'static`.
+///
+/// ```ignore
+/// let error: Error = io_error().into();
+/// let _: &std::io::Error = error.downcast_ref().unwrap();
+/// ```
+///
 /// # Backtrace
 ///
 /// Another use case is to capture backtrace when the error is created. Without
@@ -271,6 +508,147 @@ pub fn derive_macro(input: TokenStream) -> TokenStream {
 /// let backtrace: &Backtrace = std::error::request_ref(&error).unwrap();
 /// ```
 ///
+/// To always capture a backtrace where the new type is created, even if the
+/// original error type provides one, specify `backtrace = always` instead. This
+/// is helpful for debugging where the conversion happens. The captured one is
+/// provided in favor of the one from the original error type, which is still
+/// accessible through `inner()`.
+///
+/// # Span trace
+///
+/// Similarly, specify `#[thiserror_ext(newtype(.., spantrace))]` to capture a
+/// `SpanTrace` from `tracing-error` when the error is created, which requires
+/// the `tracing` feature. It can be combined with `backtrace`.
+///
+/// The span trace is accessible with the generated `span_trace` method. With
+/// the `backtrace` feature, it's also [`provide`]d, and is captured **only if**
+/// the original error type does not provide one, in which case `span_trace`
+/// returns `None`.
+///
+/// ## Example
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = Error, spantrace))]
+/// enum ErrorKind {
+///     #[error("foo")]
+///     Foo,
+/// }
+///
+/// let error: Error = ErrorKind::Foo.into();
+/// println!("{}", error.span_trace().unwrap());
+/// ```
+///
+/// # Diagnostic
+///
+/// Specify `#[thiserror_ext(newtype(.., diagnostic))]` to implement
+/// `miette::Diagnostic` for the new type by forwarding to the inner error,
+/// which must implement it as well. So the diagnostic information like the
+/// code, help and labels are kept after wrapping. This requires the `miette`
+/// feature.
+///
+/// # Report debug
+///
+/// Specify `#[thiserror_ext(newtype(.., report_debug))]` to implement [`Debug`]
+/// for the new type by delegating to the report of the error, same as deriving
+/// [`ReportDebug`]. Otherwise, the new type delegates [`Debug`] to the original
+/// error type.
+///
+/// [`Debug`]: std::fmt::Debug
+///
+/// # Deref
+///
+/// Specify `#[thiserror_ext(newtype(.., deref))]` to implement [`Deref`] for
+/// the new type, targeting the original error type. This allows matching on
+/// `&*error` and calling the methods of the original error type directly.
+///
+/// Note that the target is the original error type, not `dyn Error`. The
+/// methods of [`Error`] and the inherent methods of the new type, like
+/// `inner()`, still resolve to the new type itself.
+///
+/// [`Deref`]: std::ops::Deref
+/// [`Error`]: std::error::Error
+///
+/// # Clone
+///
+/// Specify `#[thiserror_ext(newtype(.., clone))]` to implement [`Clone`] for
+/// the new type by cloning the inner error into a new box. The original error
+/// type must implement [`Clone`]. The captured backtrace, if any, is shared
+/// between the clones.
+///
+/// This is not needed for [`derive@Arc`], whose new type is always [`Clone`].
+///
+/// # Equality
+///
+/// Specify `#[thiserror_ext(newtype(.., eq))]` to implement [`PartialEq`] for
+/// the new type by comparing the inner errors, which is helpful for asserting
+/// on errors in tests. The original error type must implement [`PartialEq`].
+/// [`Eq`] is also implemented if the original error type implements it.
+///
+/// ## Example
+///
+/// ```ignore
+/// #[derive(Debug, PartialEq, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = Error, eq))]
+/// enum ErrorKind {
+///     #[error("foo")]
+///     Foo,
+/// }
+///
+/// assert_eq!(Error::from(ErrorKind::Foo), ErrorKind::Foo.into());
+/// ```
+///
+/// Similarly, specify `#[thiserror_ext(newtype(.., hash))]` to implement
+/// [`Hash`] for the new type by hashing the inner error, so that it can be
+/// used as a key in a `HashMap` or `HashSet`. The original error type must
+/// implement [`Hash`]. As `Hash` must be consistent with
+/// `PartialEq`, a warning is emitted if `hash` is specified without `eq`.
+///
+/// # Variant accessors
+///
+/// Specify `#[thiserror_ext(newtype(.., variant_accessors))]` to generate an
+/// `is_*` method on the new type for each variant of the original enum, so
+/// that it can be checked without calling `inner()` and pattern matching.
+/// Variants with a `#[from]` field are skipped.
+///
+/// An `as_*` method is also generated for each variant, returning the
+/// references to its fields if the inner error is of that variant, or `None`
+/// otherwise:
+///
+/// - for unit variants, `Option<()>`;
+/// - for tuple variants with a single field, `Option<&T>`;
+/// - for tuple variants with multiple fields, a tuple of the references;
+/// - for variants with named fields, a generated struct named `{Variant}Ref`
+///   with the references as the fields of the same names.
+///
+/// ## Example
+///
+/// ```ignore
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = Error, variant_accessors))]
+/// enum ErrorKind {
+///     #[error("not found")]
+///     NotFound,
+///     #[error("timeout after {0:?}")]
+///     Timeout(Duration),
+/// }
+///
+/// let error: Error = ErrorKind::NotFound.into();
+/// assert!(error.is_not_found());
+/// assert!(!error.is_timeout());
+///
+/// let error: Error = ErrorKind::Timeout(Duration::from_secs(1)).into();
+/// assert_eq!(error.as_timeout(), Some(&Duration::from_secs(1)));
+/// ```
+///
+/// # Generics
+///
+/// If the original type is generic, the new type carries the same generic
+/// parameters and bounds, e.g., `Error<T>` for `ErrorKind<T>`. The trait
+/// implementations like `PartialEq` and `Clone` are bounded on the original
+/// type implementing them. `variant_accessors` is not supported for generic
+/// types.
+///
 /// [`Backtrace`]: std::backtrace::Backtrace
 /// [`provide`]: std::error::Error::provide
 #[proc_macro_derive(Box, attributes(thiserror_ext))]
@@ -290,6 +668,10 @@ pub fn derive_box(input: TokenStream) -> TokenStream {
 /// in an [`Arc`], so that it can always be cloned and shared across threads.
 /// See [`thiserror_ext::Box`] for the explanation and examples.
 ///
+/// As the inner error can be shared, `into_inner` is not available. Instead,
+/// `try_into_inner` and `get_mut` are generated to access the inner error
+/// exclusively, which only succeed if there's no other reference to it.
+///
 /// [`Arc`]: std::sync::Arc
 /// [`thiserror_ext::Box`]: derive@Box
 #[proc_macro_derive(Arc, attributes(thiserror_ext))]
@@ -336,6 +718,11 @@ pub fn derive_arc(input: TokenStream) -> TokenStream {
 /// Since the new type delegates its [`Debug`] implementation to the original
 /// error type, if the original error type derives [`ReportDebug`], the new type
 /// will also behave the same.
+///
+/// Alternatively, specify `#[thiserror_ext(newtype(.., report_debug))]` to
+/// implement the same behavior on the new type only, without deriving
+/// [`ReportDebug`] on the original error type. In this case, the backtrace
+/// captured by the new type is also shown.
 #[proc_macro_derive(ReportDebug)]
 pub fn derive_report_debug(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -344,3 +731,50 @@ pub fn derive_report_debug(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Generates the [`Display`] implementation for a wrapper of an error that
+/// delegates to the [`Report`] of the wrapped error.
+///
+/// This is useful when the error is passed to somewhere that only formats it
+/// with [`Display`], like a generic logging layer, while the source chain is
+/// still expected to be shown. The alternate flag (`#`) is passed through to
+/// select the multi-line format.
+///
+/// The derive can only be applied to a struct with a single field of the
+/// wrapped error. This is because [`Report`] formats the error itself with
+/// [`Display`], so delegating the [`Display`] of an error to its own report
+/// would recurse infinitely. For the same reason, it cannot be used together
+/// with `#[error(..)]` from `thiserror`, which also generates [`Display`].
+///
+/// # Example
+/// ```ignore
+/// #[derive(thiserror::Error, Debug)]
+/// #[error("outer")]
+/// struct Outer {
+///     #[source]
+///     inner: Inner,
+/// }
+///
+/// #[derive(thiserror_ext::ReportDisplay)]
+/// struct Displayed(Outer);
+///
+/// // outer: inner
+/// println!("{}", Displayed(outer));
+/// ```
+///
+/// [`Display`]: std::fmt::Display
+/// [`Report`]: thiserror_ext::Report
+///
+/// # New type
+///
+/// Since the new type delegates its [`Display`] implementation to the original
+/// error type, the new type of a wrapper deriving [`ReportDisplay`] will also
+/// behave the same.
+#[proc_macro_derive(ReportDisplay)]
+pub fn derive_report_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand::derive_report_display(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -3,8 +3,8 @@ use quote::{format_ident, quote, ToTokens};
 use std::collections::BTreeSet as Set;
 use syn::parse::ParseStream;
 use syn::{
-    braced, bracketed, parenthesized, token, Attribute, Error, Ident, Index, LitInt, LitStr, Meta,
-    Result, Token,
+    braced, bracketed, parenthesized, token, Attribute, Error, Ident, Index, LitBool, LitInt,
+    LitStr, Meta, Result, Token, Type, Visibility,
 };
 
 pub struct Attrs<'a> {
@@ -13,9 +13,22 @@ pub struct Attrs<'a> {
     pub backtrace: Option<&'a Attribute>,
     pub from: Option<&'a Attribute>,
     pub message: Option<&'a Attribute>,
+    pub location: Option<&'a Attribute>,
     pub transparent: Option<Transparent<'a>>,
     pub construct_skip: Option<&'a Attribute>,
+    pub construct_name: Option<Ident>,
+    pub construct_aliases: Vec<Ident>,
+    pub construct_builder: Option<&'a Attribute>,
+    pub construct_default: Option<&'a Attribute>,
+    pub construct_vis: Option<Visibility>,
+    pub construct_const: Option<&'a Attribute>,
+    pub construct_into: Option<bool>,
+    pub construct_try: Option<&'a Attribute>,
+    pub construct_try_error: Option<Type>,
     pub context_into_skip: Option<&'a Attribute>,
+    pub context_into_name: Option<Ident>,
+    pub context_into_with: Option<bool>,
+    pub context_into_marker: Option<Ident>,
 }
 
 #[derive(Clone)]
@@ -53,9 +66,22 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
         backtrace: None,
         from: None,
         message: None,
+        location: None,
         transparent: None,
         construct_skip: None,
+        construct_name: None,
+        construct_aliases: Vec::new(),
+        construct_builder: None,
+        construct_default: None,
+        construct_vis: None,
+        construct_const: None,
+        construct_into: None,
+        construct_try: None,
+        construct_try_error: None,
         context_into_skip: None,
+        context_into_name: None,
+        context_into_with: None,
+        context_into_marker: None,
     };
 
     for attr in input {
@@ -91,13 +117,60 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
                 return Err(Error::new_spanned(attr, "duplicate #[message] attribute"));
             }
             attrs.message = Some(attr);
+        } else if attr.path().is_ident("location") {
+            attr.meta.require_path_only()?;
+            if attrs.location.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[location] attribute"));
+            }
+            attrs.location = Some(attr);
         } else if attr.path().is_ident("construct") {
             attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("skip") {
                     attrs.construct_skip = Some(attr);
                     Ok(())
+                } else if meta.path.is_ident("name") {
+                    if attrs.construct_name.is_some() {
+                        return Err(Error::new_spanned(attr, "duplicate `name` attribute"));
+                    }
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.construct_name = Some(parse_name(&name)?);
+                    Ok(())
+                } else if meta.path.is_ident("alias") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.construct_aliases.push(parse_name(&name)?);
+                    Ok(())
+                } else if meta.path.is_ident("builder") {
+                    attrs.construct_builder = Some(attr);
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    attrs.construct_default = Some(attr);
+                    Ok(())
+                } else if meta.path.is_ident("const") {
+                    attrs.construct_const = Some(attr);
+                    Ok(())
+                } else if meta.path.is_ident("into") {
+                    let into: LitBool = meta.value()?.parse()?;
+                    attrs.construct_into = Some(into.value);
+                    Ok(())
+                } else if meta.path.is_ident("try") {
+                    attrs.construct_try = Some(attr);
+                    if meta.input.peek(Token![=]) {
+                        attrs.construct_try_error = Some(meta.value()?.parse()?);
+                    }
+                    Ok(())
+                } else if meta.path.is_ident("vis") {
+                    let value = meta.value()?;
+                    attrs.construct_vis = Some(if let Ok(lit_str) = value.parse::<LitStr>() {
+                        lit_str.parse()?
+                    } else {
+                        value.parse()?
+                    });
+                    Ok(())
                 } else {
-                    Err(Error::new_spanned(attr, "expected `skip`"))
+                    Err(Error::new_spanned(
+                        attr,
+                        "expected `skip`, `name`, `alias`, `builder`, `default`, `const`, `into`, `try` or `vis`",
+                    ))
                 }
             })?;
         } else if attr.path().is_ident("context_into") {
@@ -105,8 +178,28 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
                 if meta.path.is_ident("skip") {
                     attrs.context_into_skip = Some(attr);
                     Ok(())
+                } else if meta.path.is_ident("name") {
+                    if attrs.context_into_name.is_some() {
+                        return Err(Error::new_spanned(attr, "duplicate `name` attribute"));
+                    }
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.context_into_name = Some(parse_name(&name)?);
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    attrs.context_into_with = Some(true);
+                    Ok(())
+                } else if meta.path.is_ident("no_with") {
+                    attrs.context_into_with = Some(false);
+                    Ok(())
+                } else if meta.path.is_ident("marker") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.context_into_marker = Some(parse_name(&name)?);
+                    Ok(())
                 } else {
-                    Err(Error::new_spanned(attr, "expected `skip`"))
+                    Err(Error::new_spanned(
+                        attr,
+                        "expected `skip`, `name`, `with`, `no_with` or `marker`",
+                    ))
                 }
             })?;
         }
@@ -115,6 +208,15 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
     Ok(attrs)
 }
 
+fn parse_name(name: &LitStr) -> Result<Ident> {
+    name.parse().map_err(|_| {
+        Error::new_spanned(
+            name,
+            format!("invalid name `{}`, expected an identifier", name.value()),
+        )
+    })
+}
+
 fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Result<()> {
     syn::custom_keyword!(transparent);
 
@@ -18,6 +18,10 @@ impl Struct<'_> {
         message_field(&self.fields)
     }
 
+    pub(crate) fn location_field(&self) -> Option<&Field> {
+        location_field(&self.fields)
+    }
+
     pub(crate) fn distinct_backtrace_field(&self) -> Option<&Field> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
@@ -68,6 +72,10 @@ impl Variant<'_> {
         message_field(&self.fields)
     }
 
+    pub(crate) fn location_field(&self) -> Option<&Field> {
+        location_field(&self.fields)
+    }
+
     pub(crate) fn distinct_backtrace_field(&self) -> Option<&Field> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
@@ -94,18 +102,16 @@ impl Field<'_> {
         }
     }
 
-    /// WHether this field is the `message` field.
-    pub(crate) fn is_message(&self) -> bool {
-        if self.attrs.message.is_some() {
-            true
-        } else if matches!(
-            &self.member,
-            Member::Named(ident) if ident == "message"
-        ) {
-            true
-        } else {
-            false
-        }
+    /// Whether the generated constructors take `impl Into<T>` for this field,
+    /// instead of the exact type. Disabled with `#[construct(into = false)]`.
+    pub(crate) fn construct_into(&self) -> bool {
+        self.attrs.construct_into != Some(false)
+    }
+
+    /// Whether this field is named `message`, which makes it the `message`
+    /// field if no field is marked with `#[message]`.
+    fn is_named_message(&self) -> bool {
+        matches!(&self.member, Member::Named(ident) if ident == "message")
     }
 }
 
@@ -163,13 +169,22 @@ fn distinct_backtrace_field<'a, 'b>(
 
 fn message_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     for field in fields {
-        if field.is_message() {
+        if field.attrs.message.is_some() {
+            return Some(field);
+        }
+    }
+    for field in fields {
+        if field.is_named_message() {
             return Some(field);
         }
     }
     None
 }
 
+fn location_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
+    fields.iter().find(|field| field.attrs.location.is_some())
+}
+
 fn type_is_backtrace(ty: &Type) -> bool {
     let path = match ty {
         Type::Path(ty) => &ty.path,
@@ -1,5 +1,9 @@
 /// Extension trait for [`Error`] that casts the error to a trait object.
 ///
+/// Smart pointers of error trait objects like `Box<dyn Error>` are not
+/// implemented directly, as this conflicts with the blanket implementation for
+/// all [`Error`]s. However, `boxed.as_dyn()` still works due to auto-deref.
+///
 /// [`Error`]: std::error::Error
 pub trait AsDyn: crate::error_sealed::Sealed {
     /// Casts the error to a trait object.
@@ -1,11 +1,24 @@
 /// Provides backtrace to the error.
+///
+/// This is the type parameter of the new types generated by [`derive@Box`]
+/// and [`derive@Arc`], which decides whether to capture an extra backtrace
+/// when wrapping an error. It can be reused in user-defined wrappers as well,
+/// by calling [`capture`](WithBacktrace::capture) on construction and
+/// `provide` in [`Error::provide`].
+///
+/// [`derive@Box`]: crate::Box
+/// [`derive@Arc`]: crate::Arc
+/// [`Error::provide`]: std::error::Error::provide
 pub trait WithBacktrace {
     /// Capture backtrace based on whether the error already has one.
     fn capture(inner: &dyn std::error::Error) -> Self;
 
     #[cfg(feature = "backtrace")]
     /// Provide the backtrace, if any.
-    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>);
+    ///
+    /// Provides nothing by default, so that implementations written without
+    /// the `backtrace` feature keep compiling when it is enabled.
+    fn provide<'a>(&'a self, _request: &mut std::error::Request<'a>) {}
 }
 
 /// Do not capture extra backtrace.
@@ -16,23 +29,24 @@ impl WithBacktrace for NoExtraBacktrace {
     fn capture(_inner: &dyn std::error::Error) -> Self {
         Self
     }
-
-    #[cfg(feature = "backtrace")]
-    fn provide<'a>(&'a self, _request: &mut std::error::Request<'a>) {}
 }
 
 #[cfg(feature = "backtrace")]
 mod maybe {
     use super::WithBacktrace;
     use std::backtrace::Backtrace;
+    use std::sync::Arc;
 
     /// Capture backtrace if the error does not already have one.
-    pub struct MaybeBacktrace(Option<Backtrace>);
+    ///
+    /// The backtrace is shared when cloned, as [`Backtrace`] is not `Clone`.
+    #[derive(Clone)]
+    pub struct MaybeBacktrace(Option<Arc<Backtrace>>);
 
     impl WithBacktrace for MaybeBacktrace {
         fn capture(inner: &dyn std::error::Error) -> Self {
             let inner = if std::error::request_ref::<Backtrace>(inner).is_none() {
-                Some(Backtrace::capture())
+                Some(Arc::new(Backtrace::capture()))
             } else {
                 None
             };
@@ -41,11 +55,77 @@ mod maybe {
 
         fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
             if let Some(backtrace) = &self.0 {
-                request.provide_ref(backtrace);
+                request.provide_ref::<Backtrace>(backtrace);
             }
         }
     }
+
+    /// Always capture backtrace, even if the error already has one.
+    ///
+    /// The backtrace is shared when cloned, as [`Backtrace`] is not `Clone`.
+    #[derive(Clone)]
+    pub struct AlwaysBacktrace(Arc<Backtrace>);
+
+    impl WithBacktrace for AlwaysBacktrace {
+        fn capture(_inner: &dyn std::error::Error) -> Self {
+            Self(Arc::new(Backtrace::capture()))
+        }
+
+        fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+            // Provided before the inner error, so this one takes precedence.
+            request.provide_ref::<Backtrace>(&self.0);
+        }
+    }
 }
 
 #[cfg(feature = "backtrace")]
-pub use maybe::MaybeBacktrace;
+pub use maybe::{AlwaysBacktrace, MaybeBacktrace};
+
+#[cfg(feature = "tracing")]
+mod span_trace {
+    use super::{NoExtraBacktrace, WithBacktrace};
+    use tracing_error::SpanTrace;
+
+    /// Capture span trace if the error does not already have one, in addition
+    /// to the backtrace captured by `B`.
+    #[derive(Clone)]
+    pub struct WithSpanTrace<B = NoExtraBacktrace> {
+        span_trace: Option<SpanTrace>,
+        #[cfg_attr(not(feature = "backtrace"), allow(dead_code))]
+        backtrace: B,
+    }
+
+    impl<B> WithSpanTrace<B> {
+        /// Returns the captured span trace, if any.
+        pub fn span_trace(&self) -> Option<&SpanTrace> {
+            self.span_trace.as_ref()
+        }
+    }
+
+    impl<B: WithBacktrace> WithBacktrace for WithSpanTrace<B> {
+        fn capture(inner: &dyn std::error::Error) -> Self {
+            // Without the `backtrace` feature, there's no way to tell whether
+            // the error already has one.
+            #[cfg(feature = "backtrace")]
+            let has_span_trace = std::error::request_ref::<SpanTrace>(inner).is_some();
+            #[cfg(not(feature = "backtrace"))]
+            let has_span_trace = false;
+
+            Self {
+                span_trace: (!has_span_trace).then(SpanTrace::capture),
+                backtrace: B::capture(inner),
+            }
+        }
+
+        #[cfg(feature = "backtrace")]
+        fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+            if let Some(span_trace) = &self.span_trace {
+                request.provide_ref(span_trace);
+            }
+            self.backtrace.provide(request);
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+pub use span_trace::WithSpanTrace;
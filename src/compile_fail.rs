@@ -0,0 +1,192 @@
+//! Tests asserting that misuses of the derive macros are rejected or warned at
+//! compile time, run as doctests. Each `compile_fail` case comes with a passing
+//! counterpart, so that it cannot fail for an unrelated reason.
+
+/// Specifying both `kind` and `kind_alias` on a new type is an error, as both
+/// generate the `kind` method.
+///
+/// ```compile_fail
+/// #![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+///
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = MyError, kind, kind_alias))]
+/// enum MyErrorInner {
+///     #[error("internal")]
+///     Internal,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```
+/// #![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+///
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = MyError, kind_alias))]
+/// enum MyErrorInner {
+///     #[error("internal")]
+///     Internal,
+/// }
+/// # fn main() {}
+/// ```
+pub struct KindConflict;
+
+/// A variant without a `message` field gets no macros, which is warned with a
+/// deprecation lint on the variant, unless skipped explicitly.
+///
+/// ```compile_fail
+/// #![deny(deprecated)]
+///
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Macro)]
+/// enum MyError {
+///     #[error("foo: {message}")]
+///     Foo { message: String },
+///
+///     #[error("bar")]
+///     Bar,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```
+/// #![deny(deprecated)]
+///
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Macro)]
+/// enum MyError {
+///     #[error("foo: {message}")]
+///     Foo { message: String },
+///
+///     #[error("bar")]
+///     #[thiserror_ext(macro(skip))]
+///     Bar,
+/// }
+/// # fn main() {}
+/// ```
+pub struct MissingMessageField;
+
+/// A constructor with a restricted visibility cannot be called from outside
+/// of it. As a doctest is a single crate, a module boundary stands in for the
+/// crate boundary here.
+///
+/// ```compile_fail
+/// mod outer {
+///     pub mod errors {
+///         #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+///         pub enum MyError {
+///             #[error("internal")]
+///             #[construct(vis = pub(super))]
+///             Internal,
+///         }
+///     }
+/// }
+///
+/// fn main() {
+///     let _ = outer::errors::MyError::internal();
+/// }
+/// ```
+///
+/// ```
+/// mod outer {
+///     pub mod errors {
+///         #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+///         pub enum MyError {
+///             #[error("internal")]
+///             #[construct(vis = pub(crate))]
+///             Internal,
+///         }
+///     }
+/// }
+///
+/// fn main() {
+///     let _ = outer::errors::MyError::internal();
+/// }
+/// ```
+pub struct ConstructVisRestricted;
+
+/// The visibility of a constructor cannot be broader than the type.
+///
+/// ```compile_fail
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// pub(crate) enum MyError {
+///     #[error("internal")]
+///     #[construct(vis = pub)]
+///     Internal,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// pub(crate) enum MyError {
+///     #[error("internal")]
+///     #[construct(vis = pub(crate))]
+///     Internal,
+/// }
+/// # fn main() {}
+/// ```
+pub struct ConstructVisBroader;
+
+/// The name of a constructor must be an identifier. Keywords are only allowed
+/// as raw identifiers.
+///
+/// ```compile_fail
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// enum MyError {
+///     #[error("type mismatch")]
+///     #[construct(name = "type")]
+///     Type,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```compile_fail
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// enum MyError {
+///     #[error("http error")]
+///     #[construct(name = "http-error")]
+///     HttpError,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```
+/// #[derive(Debug, thiserror::Error, thiserror_ext::Construct)]
+/// enum MyError {
+///     #[error("type mismatch")]
+///     #[construct(name = "r#type")]
+///     Type,
+///
+///     #[error("http error")]
+///     #[construct(name = "http_error")]
+///     HttpError,
+/// }
+/// # fn main() {}
+/// ```
+pub struct ConstructInvalidName;
+
+/// Specifying `hash` on a new type requires the original error type to
+/// implement `Hash`.
+///
+/// ```compile_fail
+/// #![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+///
+/// #[derive(Debug, PartialEq, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = MyError, eq, hash))]
+/// enum MyErrorInner {
+///     #[error("internal")]
+///     Internal,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ```
+/// #![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+///
+/// #[derive(Debug, PartialEq, Hash, thiserror::Error, thiserror_ext::Box)]
+/// #[thiserror_ext(newtype(name = MyError, eq, hash))]
+/// enum MyErrorInner {
+///     #[error("internal")]
+///     Internal,
+/// }
+/// # fn main() {}
+/// ```
+pub struct HashWithoutInnerHash;
@@ -21,20 +21,37 @@
 
 mod as_dyn;
 mod backtrace;
+#[cfg(doctest)]
+mod compile_fail;
 mod ptr;
 mod report;
 
 pub use as_dyn::AsDyn;
-pub use report::{AsReport, Report};
+#[cfg(feature = "tracing")]
+pub use backtrace::WithSpanTrace;
+#[cfg(feature = "backtrace")]
+pub use backtrace::{AlwaysBacktrace, MaybeBacktrace};
+pub use backtrace::{NoExtraBacktrace, WithBacktrace};
+#[cfg(feature = "color")]
+pub use report::ColoredReport;
+pub use report::{AsReport, Chain, CleanedErrorText, FormattedReport, Report, ReportHeaders};
 pub use thiserror_ext_derive::*;
 
 #[doc(hidden)]
 pub mod __private {
-    #[cfg(feature = "backtrace")]
-    pub use crate::backtrace::MaybeBacktrace;
     pub use crate::backtrace::NoExtraBacktrace;
+    #[cfg(feature = "tracing")]
+    pub use crate::backtrace::WithSpanTrace;
+    #[cfg(feature = "backtrace")]
+    pub use crate::backtrace::{AlwaysBacktrace, MaybeBacktrace};
     pub use crate::ptr::{ErrorArc, ErrorBox};
+    #[cfg(feature = "miette")]
+    pub use miette;
     pub use thiserror;
+    #[cfg(feature = "tracing")]
+    pub use tracing;
+    #[cfg(feature = "tracing")]
+    pub use tracing_error::SpanTrace;
 }
 
 macro_rules! for_dyn_error_types {
@@ -8,13 +8,23 @@ use crate::backtrace::WithBacktrace;
 pub struct ErrorBox<T, B>(Box<(T, B)>);
 
 impl<T, B> ErrorBox<T, B> {
+    /// Returns a mutable reference to the inner error.
     pub fn inner_mut(&mut self) -> &mut T {
         &mut self.0.as_mut().0
     }
 
+    /// Consumes the box and returns the inner error, dropping the captured
+    /// backtrace.
     pub fn into_inner(self) -> T {
         (*self.0).0
     }
+
+    /// Transforms the inner error with the given function, preserving the
+    /// captured backtrace.
+    pub fn map_inner<U>(self, f: impl FnOnce(T) -> U) -> ErrorBox<U, B> {
+        let (t, backtrace) = *self.0;
+        ErrorBox(Box::new((f(t), backtrace)))
+    }
 }
 
 impl<T, B> std::ops::DerefMut for ErrorBox<T, B> {
@@ -23,10 +33,42 @@ impl<T, B> std::ops::DerefMut for ErrorBox<T, B> {
     }
 }
 
+impl<T, B> AsMut<T> for ErrorBox<T, B> {
+    fn as_mut(&mut self) -> &mut T {
+        self.inner_mut()
+    }
+}
+
 /// A [`Arc`] with optional backtrace.
 #[repr(transparent)]
 pub struct ErrorArc<T, B>(Arc<(T, B)>);
 
+impl<T, B> ErrorArc<T, B> {
+    /// Returns the inner error if this is the only reference to it, dropping
+    /// the captured backtrace. Otherwise, `self` is returned unchanged.
+    pub fn try_into_inner(self) -> Result<T, Self> {
+        Arc::try_unwrap(self.0).map(|(t, _)| t).map_err(Self)
+    }
+
+    /// Returns a mutable reference to the inner error if this is the only
+    /// reference to it.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        Arc::get_mut(&mut self.0).map(|(t, _)| t)
+    }
+
+    /// Transforms a clone of the inner error with the given function, preserving
+    /// the captured backtrace. The inner error can't be moved out as it may be
+    /// shared.
+    pub fn map_cloned<U>(&self, f: impl FnOnce(T) -> U) -> ErrorArc<U, B>
+    where
+        T: Clone,
+        B: Clone,
+    {
+        let (t, backtrace) = self.0.as_ref();
+        ErrorArc(Arc::new((f(t.clone()), backtrace.clone())))
+    }
+}
+
 impl<T, B> Clone for ErrorArc<T, B> {
     fn clone(&self) -> Self {
         Self(self.0.clone())
@@ -36,6 +78,7 @@ impl<T, B> Clone for ErrorArc<T, B> {
 macro_rules! impl_methods {
     ($ty:ident) => {
         impl<T: std::error::Error, B: WithBacktrace> $ty<T, B> {
+            /// Wraps the error, capturing the backtrace with `B` if needed.
             pub fn new(t: T) -> Self {
                 let backtrace = B::capture(&t);
                 Self((t, backtrace).into())
@@ -48,11 +91,20 @@ macro_rules! impl_methods {
                 &self.0.as_ref().1
             }
 
+            /// Returns the reference to the inner error.
             pub fn inner(&self) -> &T {
                 &self.0.as_ref().0
             }
         }
 
+        #[cfg(feature = "tracing")]
+        impl<T, B> $ty<T, crate::backtrace::WithSpanTrace<B>> {
+            /// Returns the span trace captured when the error was wrapped, if any.
+            pub fn span_trace(&self) -> Option<&tracing_error::SpanTrace> {
+                self.backtrace().span_trace()
+            }
+        }
+
         impl<T, B> std::ops::Deref for $ty<T, B> {
             type Target = T;
 
@@ -61,6 +113,20 @@ macro_rules! impl_methods {
             }
         }
 
+        impl<T, B> AsRef<T> for $ty<T, B> {
+            fn as_ref(&self) -> &T {
+                self.inner()
+            }
+        }
+
+        // `Eq`, `Ord` and `Hash` are not implemented for the pointer, so they
+        // can't be inconsistent with the ones of the inner error.
+        impl<T, B> std::borrow::Borrow<T> for $ty<T, B> {
+            fn borrow(&self) -> &T {
+                self.inner()
+            }
+        }
+
         impl<T: std::fmt::Display, B> std::fmt::Display for $ty<T, B> {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 self.inner().fmt(f)
@@ -15,11 +15,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::fmt;
 
 /// Extension trait for [`Error`] that provides a [`Report`] which formats
 /// the error and its sources in a cleaned-up way.
 ///
+/// References and boxes of errors like `&E` and `Box<E>` are errors as well,
+/// while `Box<dyn Error>` is covered by auto-deref in method calls, so
+/// `.as_report()` works on all of them.
+///
+/// The same applies to `anyhow::Error` and `eyre::Report`, which dereference
+/// to the underlying `dyn Error`. The report walks their chains as is,
+/// including the contexts, without wrapping the error again. A dedicated
+/// implementation is not possible, as it would conflict with the one for all
+/// [`Error`]s.
+///
+/// Note that the report of an `eyre::Report` bypasses the installed `eyre`
+/// handler, e.g., `color-eyre`, so the extra information captured by the
+/// handler is not shown. Format the `eyre::Report` itself to include it.
+///
 /// [`Error`]: std::error::Error
 pub trait AsReport: crate::error_sealed::Sealed {
     /// Returns a [`Report`] that formats the error and its sources in a
@@ -37,6 +52,25 @@ pub trait AsReport: crate::error_sealed::Sealed {
     /// ```
     fn as_report(&self) -> Report<'_>;
 
+    /// Returns a [`FormattedReport`] joining the messages with the given
+    /// separator in the compact format.
+    ///
+    /// This is equivalent to `self.as_report().with_separator(separator)`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// // outer error -> middle error -> inner error
+    /// println!("{}", error.as_report_with_separator(" -> "));
+    /// ```
+    fn as_report_with_separator(
+        &self,
+        separator: impl Into<Cow<'static, str>>,
+    ) -> FormattedReport<'_> {
+        self.as_report().with_separator(separator)
+    }
+
     /// Converts the error to a [`Report`] and formats it in a compact way.
     ///
     /// This is equivalent to `format!("{}", self.as_report())`.
@@ -49,6 +83,41 @@ pub trait AsReport: crate::error_sealed::Sealed {
         format!("{}", self.as_report())
     }
 
+    /// Converts the error to a [`Report`] and formats it in a compact way,
+    /// truncated to at most `max_len` bytes.
+    ///
+    /// If the output is too long, it's cut at the nearest word boundary and
+    /// `...` is appended, with the total length still within `max_len`. A
+    /// multi-byte character is never split.
+    ///
+    /// ## Example
+    /// ```text
+    /// outer error: middle...
+    /// ```
+    fn to_report_string_truncated(&self, max_len: usize) -> String {
+        const ELLIPSIS: &str = "...";
+
+        let mut s = self.to_report_string();
+        if s.len() <= max_len {
+            return s;
+        }
+
+        let mut end = max_len.saturating_sub(ELLIPSIS.len());
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        // Cut at the last whitespace, unless a word ends right at `end`, or
+        // there's no whitespace at all.
+        if !s[end..].starts_with(char::is_whitespace) {
+            if let Some(i) = s[..end].rfind(char::is_whitespace) {
+                end = i;
+            }
+        }
+        s.truncate(s[..end].trim_end().len());
+        s.push_str(&ELLIPSIS[..max_len.min(ELLIPSIS.len())]);
+        s
+    }
+
     /// Converts the error to a [`Report`] and formats it in a compact way,
     /// including backtraces if available.
     ///
@@ -99,11 +168,97 @@ pub trait AsReport: crate::error_sealed::Sealed {
     fn to_report_string_pretty_with_backtrace(&self) -> String {
         format!("{:#?}", self.as_report())
     }
+
+    /// Converts the error to a [`Report`] and serializes it into a JSON string.
+    ///
+    /// See the [`Serialize`] implementation of [`Report`] for the schema,
+    /// which differs from the array produced by
+    /// [`to_json_value`](FormattedReport::to_json_value).
+    ///
+    /// ## Example
+    /// ```text
+    /// {"message":"outer error","causes":["middle error","inner error"]}
+    /// ```
+    ///
+    /// [`Serialize`]: serde::Serialize
+    #[cfg(feature = "serde")]
+    fn to_json_string(&self) -> String {
+        serde_json::to_string(&self.as_report()).expect("failed to serialize report")
+    }
+
+    /// Returns a [`ColoredReport`] that formats the error and its sources
+    /// with colors, if the output is likely to be an interactive terminal.
+    ///
+    /// See the documentation for [`ColoredReport`] for details.
+    ///
+    /// Requires the `color` feature.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// let error = fallible_action().unwrap_err();
+    /// eprintln!("{:#}", error.as_colored_report());
+    /// ```
+    #[cfg(feature = "color")]
+    fn as_colored_report(&self) -> ColoredReport<'_> {
+        ColoredReport(self.as_report().formatted())
+    }
+
+    /// Returns the first error of type `T` in the source chain, excluding the
+    /// error itself.
+    ///
+    /// The shallowest one is returned if there're multiple errors of type `T`
+    /// in the chain. The search gives up after a reasonable depth, in case of
+    /// circular source references.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// if let Some(io_error) = error.find_source::<std::io::Error>() {
+    ///     println!("io error: {}", io_error.kind());
+    /// }
+    /// ```
+    fn find_source<T: std::error::Error + 'static>(&self) -> Option<&T> {
+        sources(self.as_report().0).find_map(|source| source.downcast_ref::<T>())
+    }
+
+    /// Returns the deepest source of the error, or the error itself if there's
+    /// no source.
+    ///
+    /// See [`Report::root_cause`] for details.
+    fn root_cause(&self) -> &dyn std::error::Error {
+        self.as_report().root_cause()
+    }
+
+    /// Returns the number of sources that will be shown in the [`Report`].
+    ///
+    /// Sources with empty messages after cleaning are not counted, so this
+    /// can be less than the actual length of the source chain.
+    fn source_count(&self) -> usize {
+        self.as_report()
+            .formatted()
+            .visible_messages()
+            .len()
+            .saturating_sub(1)
+    }
+
+    /// Returns the cleaned messages of the error and its sources, which are
+    /// exactly the ones shown in the [`Report`].
+    ///
+    /// ## Example
+    /// ```text
+    /// ["outer error", "middle error", "inner error"]
+    /// ```
+    fn all_messages(&self) -> Vec<String> {
+        self.as_report().formatted().visible_messages()
+    }
 }
 
 impl<T: std::error::Error> AsReport for T {
     fn as_report(&self) -> Report<'_> {
-        Report(self)
+        Report::new(self)
     }
 }
 
@@ -112,7 +267,7 @@ macro_rules! impl_as_report {
         $(
             impl AsReport for $ty {
                 fn as_report(&self) -> Report<'_> {
-                    Report(self)
+                    Report::new(self)
                 }
             }
         )*
@@ -120,10 +275,43 @@ macro_rules! impl_as_report {
 }
 crate::for_dyn_error_types! { impl_as_report }
 
+/// The maximum depth of the source chain to walk through, in case of circular
+/// source references.
+const MAX_CHAIN_DEPTH: usize = 64;
+
+/// The message appended to the last error when the source chain is cut off
+/// due to [`MAX_CHAIN_DEPTH`].
+const CHAIN_TOO_DEEP: &str = "(cycle detected or chain too deep)";
+
+/// Returns an iterator over the sources of the error, excluding the error itself.
+fn sources<'a>(
+    error: &'a dyn std::error::Error,
+) -> impl Iterator<Item = &'a (dyn std::error::Error + 'static)> {
+    std::iter::successors(error.source(), |e| e.source()).take(MAX_CHAIN_DEPTH)
+}
+
+/// Returns the error and its sources along with their messages, not cleaned.
+/// If the chain is cut off due to [`MAX_CHAIN_DEPTH`], [`CHAIN_TOO_DEEP`] is
+/// appended to the last message, in the same way as [`CleanedErrorText`].
+fn uncleaned_messages(
+    error: &dyn std::error::Error,
+) -> impl Iterator<Item = (&dyn std::error::Error, String)> {
+    Chain::new(error).enumerate().map(|(depth, error)| {
+        let mut message = error.to_string();
+        if depth >= MAX_CHAIN_DEPTH && error.source().is_some() {
+            if !message.is_empty() {
+                message.push(' ');
+            }
+            message.push_str(CHAIN_TOO_DEEP);
+        }
+        (error, message)
+    })
+}
+
 /// A wrapper around an error that provides a cleaned up error trace for
 /// display and debug formatting.
 ///
-/// Constructed using [`AsReport::as_report`].
+/// Constructed using [`AsReport::as_report`] or [`Report::new`].
 ///
 /// # Formatting
 ///
@@ -145,8 +333,15 @@ crate::for_dyn_error_types! { impl_as_report }
 ///     2. Inner error text
 ///   ```
 ///
+///   The continuation lines of multi-line messages are indented to align with
+///   the first line.
+///
 /// - Additionally, [`fmt::Debug`] provide backtraces if available.
 ///
+/// The formatting can be further customized with the builder methods, like
+/// [`Report::with_separator`], [`Report::with_max_depth`] and
+/// [`Report::numbered`].
+///
 /// # Error source cleaning
 ///
 /// It's common for errors with a `source` to have a `Display`
@@ -174,37 +369,167 @@ crate::for_dyn_error_types! { impl_as_report }
 /// 2. Middle error text
 /// 3. Inner error text
 /// ```
+///
+/// In case the cleaning removes text that is not duplicated by coincidence,
+/// it can be disabled with [`Report::with_cleaning`].
+#[derive(Clone, Copy)]
 pub struct Report<'a>(pub &'a dyn std::error::Error);
 
-impl<'a> fmt::Display for Report<'a> {
+/// A [`Report`] with customized formatting options.
+///
+/// Returned by the builder methods of [`Report`], like [`Report::with_separator`],
+/// which can be further chained on this type. It's formatted and serialized in
+/// the same way as [`Report`], except for the customized options.
+#[derive(Clone)]
+pub struct FormattedReport<'a>(&'a dyn std::error::Error, Options);
+
+/// The predicate on the symbol names of backtrace frames, returning whether to
+/// keep the frame.
+#[cfg(feature = "backtrace")]
+type BacktraceFilter = std::sync::Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Options for formatting a [`Report`].
+#[derive(Clone)]
+struct Options {
+    /// The separator between messages in compact mode.
+    separator: Cow<'static, str>,
+    /// The headers of the sources in pretty mode.
+    headers: ReportHeaders,
+    /// The maximum number of sources to show.
+    max_depth: Option<usize>,
+    /// Whether to remove the text of each source from the containing error.
+    cleaning: bool,
+    /// Whether to put each message on its own numbered line, regardless of
+    /// the alternate flag.
+    numbered: bool,
+    /// The predicate to filter the frames of the backtrace.
+    #[cfg(feature = "backtrace")]
+    backtrace_filter: Option<BacktraceFilter>,
+    /// Whether to show the backtraces of all errors in the chain, instead of
+    /// only the one provided by the top error.
+    #[cfg(feature = "backtrace")]
+    all_backtraces: bool,
+    /// Whether to style the output with ANSI escape codes.
+    #[cfg(feature = "ansi")]
+    ansi: bool,
+    /// Whether to use the colored palette instead of the plain one when
+    /// styling the output.
+    #[cfg(feature = "color")]
+    colored: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            separator: Cow::Borrowed(": "),
+            headers: ReportHeaders::default(),
+            max_depth: None,
+            cleaning: true,
+            numbered: false,
+            #[cfg(feature = "backtrace")]
+            backtrace_filter: None,
+            #[cfg(feature = "backtrace")]
+            all_backtraces: false,
+            #[cfg(feature = "ansi")]
+            ansi: false,
+            #[cfg(feature = "color")]
+            colored: false,
+        }
+    }
+}
+
+/// The header texts preceding the sources when formatting a [`Report`] in
+/// the pretty way.
+///
+/// Used with [`Report::with_headers`].
+#[derive(Clone, Debug)]
+pub struct ReportHeaders {
+    /// The header used when there's a single source.
+    ///
+    /// Defaults to `"Caused by:"`.
+    pub single: Cow<'static, str>,
+
+    /// The header used when there're multiple sources.
+    ///
+    /// Defaults to `"Caused by these errors (recent errors listed first):"`.
+    pub multiple: Cow<'static, str>,
+}
+
+impl Default for ReportHeaders {
+    fn default() -> Self {
+        Self {
+            single: Cow::Borrowed("Caused by:"),
+            multiple: Cow::Borrowed("Caused by these errors (recent errors listed first):"),
+        }
+    }
+}
+
+impl<'a> fmt::Display for FormattedReport<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.cleaned_error_trace(f, f.alternate())
     }
 }
 
-impl<'a> fmt::Debug for Report<'a> {
+/// Serializes the report into a map of the error message and the messages of
+/// the sources, cleaned in the same way as formatting the report.
+///
+/// If the `backtrace` feature is enabled and a backtrace is captured, it's
+/// included under the `backtrace` key.
+///
+/// ```text
+/// {"message":"outer error","causes":["middle error","inner error"]}
+/// ```
+///
+/// Note that this differs from [`FormattedReport::to_json_value`], which
+/// produces an array with an object for each error in the chain instead.
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for FormattedReport<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut messages = self.visible_messages().into_iter();
+        let message = messages.next().unwrap_or_default();
+        let causes: Vec<_> = messages.collect();
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("message", &message)?;
+        map.serialize_entry("causes", &causes)?;
+        #[cfg(feature = "backtrace")]
+        if let Some(bt) = self.captured_backtrace() {
+            map.serialize_entry("backtrace", &bt.to_string())?;
+        }
+        map.end()
+    }
+}
+
+impl<'a> fmt::Debug for FormattedReport<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.cleaned_error_trace(f, f.alternate())?;
 
         #[cfg(feature = "backtrace")]
         {
-            use std::backtrace::{Backtrace, BacktraceStatus};
-
-            if let Some(bt) = std::error::request_ref::<Backtrace>(self.0) {
-                // Hack for testing purposes.
-                // Read the env var could be slow but we short-circuit it in release mode,
-                // so this should be optimized out in production.
-                let force_show_backtrace = cfg!(debug_assertions)
-                    && std::env::var("THISERROR_EXT_TEST_SHOW_USELESS_BACKTRACE").is_ok();
-
-                // If the backtrace is disabled or unsupported, behave as if there's no backtrace.
-                if bt.status() == BacktraceStatus::Captured || force_show_backtrace {
-                    // The alternate mode contains a trailing newline while non-alternate
-                    // mode does not. So we need to add a newline before the backtrace.
-                    if !f.alternate() {
-                        writeln!(f)?;
+            let backtraces = self.backtraces_to_show();
+
+            if !backtraces.is_empty() {
+                // The alternate mode contains a trailing newline while non-alternate
+                // and numbered modes do not. So we need to add a newline before the
+                // backtrace.
+                if !f.alternate() || self.1.numbered {
+                    writeln!(f)?;
+                }
+            }
+
+            for (message, bt) in backtraces {
+                match message {
+                    Some(message) => writeln!(f, "\nBacktrace of `{}`:", message)?,
+                    None => writeln!(f, "\nBacktrace:")?,
+                }
+                match &self.1.backtrace_filter {
+                    Some(filter) => {
+                        let bt = filter_backtrace(&bt.to_string(), filter.as_ref());
+                        writeln!(f, "{}", bt)?;
                     }
-                    writeln!(f, "\nBacktrace:\n{}", bt)?;
+                    None => writeln!(f, "{}", bt)?,
                 }
             }
         }
@@ -213,12 +538,537 @@ impl<'a> fmt::Debug for Report<'a> {
     }
 }
 
+/// The report itself is an error as well, delegating the sources and provided
+/// values to the inner error, so that it can be used where an [`Error`] is
+/// expected.
+///
+/// [`Error`]: std::error::Error
+impl<'a> std::error::Error for FormattedReport<'a> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+
+    #[cfg(feature = "backtrace")]
+    fn provide<'b>(&'b self, request: &mut std::error::Request<'b>) {
+        self.0.provide(request);
+    }
+}
+
+/// Same as [`FormattedReport`] with the default options.
+impl<'a> fmt::Display for Report<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.formatted(), f)
+    }
+}
+
+/// Same as [`FormattedReport`] with the default options.
+impl<'a> fmt::Debug for Report<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.formatted(), f)
+    }
+}
+
+/// Same as [`FormattedReport`] with the default options.
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Report<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.formatted().serialize(serializer)
+    }
+}
+
+/// The report itself is an error as well, delegating the sources and provided
+/// values to the inner error.
+impl<'a> std::error::Error for Report<'a> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+
+    #[cfg(feature = "backtrace")]
+    fn provide<'b>(&'b self, request: &mut std::error::Request<'b>) {
+        self.0.provide(request);
+    }
+}
+
 impl<'a> Report<'a> {
-    fn cleaned_error_trace(&self, f: &mut fmt::Formatter, pretty: bool) -> Result<(), fmt::Error> {
-        let cleaned_messages: Vec<_> = CleanedErrorText::new(self.0)
-            .flat_map(|(_error, msg, _cleaned)| Some(msg).filter(|msg| !msg.is_empty()))
+    /// Creates a new [`Report`] for the given error.
+    pub fn new(error: &'a dyn std::error::Error) -> Self {
+        Self(error)
+    }
+
+    /// Returns a [`FormattedReport`] with the default options, whose formatting
+    /// can be customized with the builder methods.
+    pub fn formatted(self) -> FormattedReport<'a> {
+        FormattedReport(self.0, Options::default())
+    }
+
+    /// Returns a [`FormattedReport`] with the separator between messages in the compact format. See
+    /// [`FormattedReport::with_separator`] for details.
+    pub fn with_separator(self, separator: impl Into<Cow<'static, str>>) -> FormattedReport<'a> {
+        self.formatted().with_separator(separator)
+    }
+
+    /// Returns a [`FormattedReport`] with the headers of the sources in the pretty format. See
+    /// [`FormattedReport::with_headers`] for details.
+    pub fn with_headers(self, headers: ReportHeaders) -> FormattedReport<'a> {
+        self.formatted().with_headers(headers)
+    }
+
+    /// Returns a [`FormattedReport`] with the maximum number of sources to show. See
+    /// [`FormattedReport::with_max_depth`] for details.
+    pub fn with_max_depth(self, max_depth: usize) -> FormattedReport<'a> {
+        self.formatted().with_max_depth(max_depth)
+    }
+
+    /// Returns a [`FormattedReport`] with whether to clean the messages. See
+    /// [`FormattedReport::with_cleaning`] for details.
+    pub fn with_cleaning(self, cleaning: bool) -> FormattedReport<'a> {
+        self.formatted().with_cleaning(cleaning)
+    }
+
+    /// Returns a [`FormattedReport`] with each message on its own numbered line. See
+    /// [`FormattedReport::numbered`] for details.
+    pub fn numbered(self) -> FormattedReport<'a> {
+        self.formatted().numbered()
+    }
+
+    /// Returns a [`FormattedReport`] with the predicate to filter the frames of
+    /// the backtrace. See [`FormattedReport::with_backtrace_filter`] for details.
+    ///
+    /// Requires the `backtrace` feature.
+    #[cfg(feature = "backtrace")]
+    pub fn with_backtrace_filter(
+        self,
+        filter: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> FormattedReport<'a> {
+        self.formatted().with_backtrace_filter(filter)
+    }
+
+    /// Returns a [`FormattedReport`] hiding the frames of the backtrace that are
+    /// usually noise. See [`FormattedReport::with_default_backtrace_filter`] for
+    /// details.
+    ///
+    /// Requires the `backtrace` feature.
+    #[cfg(feature = "backtrace")]
+    pub fn with_default_backtrace_filter(self) -> FormattedReport<'a> {
+        self.formatted().with_default_backtrace_filter()
+    }
+
+    /// Returns a [`FormattedReport`] showing the backtraces of all errors in the
+    /// chain. See [`FormattedReport::all_backtraces`] for details.
+    ///
+    /// Requires the `backtrace` feature.
+    #[cfg(feature = "backtrace")]
+    pub fn all_backtraces(self) -> FormattedReport<'a> {
+        self.formatted().all_backtraces()
+    }
+
+    /// Returns a [`FormattedReport`] styled with ANSI escape codes if enabled.
+    /// See [`FormattedReport::with_ansi`] for details.
+    ///
+    /// Requires the `ansi` feature.
+    #[cfg(feature = "ansi")]
+    pub fn with_ansi(self, ansi: bool) -> FormattedReport<'a> {
+        self.formatted().with_ansi(ansi)
+    }
+
+    /// Returns the deepest source of the error, or the error itself if there's
+    /// no source.
+    ///
+    /// The search gives up after a reasonable depth, in case of circular
+    /// source references. To find a source of a specific type, use
+    /// [`AsReport::find_source`] instead.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// // inner error
+    /// println!("{}", error.as_report().root_cause());
+    /// ```
+    pub fn root_cause(&self) -> &'a dyn std::error::Error {
+        match sources(self.0).last() {
+            Some(source) => source,
+            None => self.0,
+        }
+    }
+
+    /// Returns an iterator over the error and its sources, starting from the
+    /// error itself. Unlike [`Report::iter_cleaned`], the errors are yielded
+    /// as they are, without touching their display strings.
+    ///
+    /// The items are not `'static`, so they cannot be downcast. Use
+    /// [`AsReport::find_source`] to find a source of a specific type.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// let is_timeout = error
+    ///     .as_report()
+    ///     .chain()
+    ///     .any(|e| e.to_string().contains("timed out"));
+    /// ```
+    pub fn chain(&self) -> Chain<'a> {
+        Chain::new(self.0)
+    }
+
+    /// Returns an iterator over the error and its sources, with the text of
+    /// each source removed from the display string of the containing error.
+    ///
+    /// Each item is a tuple of the original error, the cleaned display string,
+    /// and whether the string has been cleaned. The strings may be empty after
+    /// cleaning, which are skipped when formatting the report.
+    ///
+    /// See the documentation for [`Report`] for how the cleaning works.
+    pub fn iter_cleaned(&self) -> CleanedErrorText<'a> {
+        CleanedErrorText::new(self.0)
+    }
+
+    /// Returns whether the two error chains have the same messages. See
+    /// [`FormattedReport::eq_chain`] for details.
+    pub fn eq_chain(&self, other: &Report<'_>) -> bool {
+        self.formatted().eq_chain(&other.formatted())
+    }
+
+    /// Converts the report to a JSON value. See [`FormattedReport::to_json_value`]
+    /// for the schema.
+    #[cfg(feature = "serde")]
+    pub fn to_json_value(&self) -> serde_json::Value {
+        self.formatted().to_json_value()
+    }
+}
+
+impl<'a> From<Report<'a>> for FormattedReport<'a> {
+    fn from(report: Report<'a>) -> Self {
+        report.formatted()
+    }
+}
+
+impl<'a> FormattedReport<'a> {
+    /// Sets the separator between messages when formatting in the compact
+    /// way, i.e., without the alternate flag. Defaults to `": "`.
+    ///
+    /// The pretty format is not affected.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// // outer error -> middle error -> inner error
+    /// println!("{}", error.as_report().with_separator(" -> "));
+    /// ```
+    pub fn with_separator(mut self, separator: impl Into<Cow<'static, str>>) -> Self {
+        self.1.separator = separator.into();
+        self
+    }
+
+    /// Sets the header texts preceding the sources when formatting in the
+    /// pretty way, i.e., with the alternate flag.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::{AsReport, ReportHeaders};
+    ///
+    /// let headers = ReportHeaders {
+    ///     single: "Reason:".into(),
+    ///     multiple: "Reasons:".into(),
+    /// };
+    /// println!("{:#}", error.as_report().with_headers(headers));
+    /// ```
+    pub fn with_headers(mut self, headers: ReportHeaders) -> Self {
+        self.1.headers = headers;
+        self
+    }
+
+    /// Sets the maximum number of sources to show. The rest of the sources
+    /// are omitted with an indicator like `... (3 more)`.
+    ///
+    /// Sources with empty messages after cleaning are not counted, as they're
+    /// never shown. The error itself is always shown, even if the limit is 0.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// // outer error: middle error: ... (1 more)
+    /// println!("{}", error.as_report().with_max_depth(1));
+    /// ```
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.1.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Sets whether to remove the text of each source from the display string
+    /// of the containing error. Defaults to `true`.
+    ///
+    /// If disabled, the display string of each error in the chain is shown
+    /// verbatim. This is useful if the cleaning unexpectedly removes some text,
+    /// for example, when an error message ends with its source's text by
+    /// coincidence.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// // outer error: middle error: inner error: inner error
+    /// println!("{}", error.as_report().with_cleaning(false));
+    /// ```
+    pub fn with_cleaning(mut self, cleaning: bool) -> Self {
+        self.1.cleaning = cleaning;
+        self
+    }
+
+    /// Formats the error and its sources with one message per line, prefixed
+    /// with 1-based indices and without the headers, regardless of the
+    /// alternate flag. This is useful for log pipelines that split the lines
+    /// later.
+    ///
+    /// If there's no source, only the message of the error is shown, without
+    /// the index. No newline is appended at the end.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// // 1: outer error
+    /// // 2: middle error
+    /// // 3: inner error
+    /// println!("{}", error.as_report().numbered());
+    /// ```
+    pub fn numbered(mut self) -> Self {
+        self.1.numbered = true;
+        self
+    }
+
+    /// Sets the predicate to filter the frames of the backtrace shown in the
+    /// [`fmt::Debug`] output. The predicate is called with the symbol name of
+    /// each frame, and the frame is kept only if it returns `true`. Defaults
+    /// to showing all frames.
+    ///
+    /// See [`Report::with_default_backtrace_filter`] for hiding the frames
+    /// that are usually noise.
+    ///
+    /// Requires the `backtrace` feature.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// let report = error.as_report().with_backtrace_filter(|frame| frame.starts_with("my_crate::"));
+    /// println!("{:?}", report);
+    /// ```
+    #[cfg(feature = "backtrace")]
+    pub fn with_backtrace_filter(
+        mut self,
+        filter: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.1.backtrace_filter = Some(std::sync::Arc::new(filter));
+        self
+    }
+
+    /// Shows the backtraces provided by all errors in the chain in the
+    /// [`fmt::Debug`] output, each labeled with the message of the error that
+    /// captured it. By default, only the backtrace provided by the top error
+    /// is shown.
+    ///
+    /// This is useful for diagnosing where the backtraces were captured when
+    /// an error is converted multiple times. A backtrace propagated with
+    /// `#[backtrace]` is only shown once, attributed to the deepest error
+    /// providing it.
+    ///
+    /// Requires the `backtrace` feature.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// println!("{:?}", error.as_report().all_backtraces());
+    /// ```
+    #[cfg(feature = "backtrace")]
+    pub fn all_backtraces(mut self) -> Self {
+        self.1.all_backtraces = true;
+        self
+    }
+
+    /// Hides the frames of the backtrace that are usually noise when shown in
+    /// the [`fmt::Debug`] output, like the ones from `core`, the runtime of
+    /// `std`, and `thiserror_ext` itself.
+    ///
+    /// Requires the `backtrace` feature.
+    #[cfg(feature = "backtrace")]
+    pub fn with_default_backtrace_filter(self) -> Self {
+        self.with_backtrace_filter(|frame| !is_noise_frame(frame))
+    }
+
+    /// Sets whether to style the output with ANSI escape codes, e.g., the
+    /// head message in bold and the source headers dimmed. Defaults to `false`.
+    ///
+    /// Even if enabled, no escape code is emitted if the `NO_COLOR`
+    /// environment variable is set to a non-empty value.
+    ///
+    /// Requires the `ansi` feature.
+    #[cfg(feature = "ansi")]
+    pub fn with_ansi(mut self, ansi: bool) -> Self {
+        self.1.ansi = ansi;
+        self
+    }
+
+    /// Returns whether the two error chains have the same messages, skipping the
+    /// empty ones in the same way as formatting the reports. Each chain is
+    /// cleaned if enabled on its own report. The types of the errors are not
+    /// compared.
+    ///
+    /// This is useful for asserting errors in tests without snapshotting the
+    /// formatted reports.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// let report = error.as_report().with_cleaning(false);
+    /// assert!(report.eq_chain(&expected.as_report().formatted()));
+    /// ```
+    pub fn eq_chain(&self, other: &FormattedReport<'_>) -> bool {
+        self.visible_messages() == other.visible_messages()
+    }
+
+    /// Converts the report to a JSON array of the error and its sources, with
+    /// the messages cleaned in the same way as formatting the report.
+    ///
+    /// If the `backtrace` feature is enabled and a backtrace is captured, it's
+    /// included in the first element.
+    ///
+    /// Note that this differs from the [`Serialize`] implementation and
+    /// [`AsReport::to_json_string`], which produce a map of the message and
+    /// the causes instead.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use thiserror_ext::AsReport;
+    ///
+    /// // [{"message":"outer error"},{"message":"middle error"},{"message":"inner error"}]
+    /// println!("{}", error.as_report().to_json_value());
+    /// ```
+    ///
+    /// [`Serialize`]: serde::Serialize
+    #[cfg(feature = "serde")]
+    pub fn to_json_value(&self) -> serde_json::Value {
+        #[allow(unused_mut)]
+        let mut values: Vec<_> = self
+            .visible_messages()
+            .into_iter()
+            .map(|msg| serde_json::json!({ "message": msg }))
             .collect();
 
+        #[cfg(feature = "backtrace")]
+        if let (Some(bt), Some(serde_json::Value::Object(head))) =
+            (self.captured_backtrace(), values.first_mut())
+        {
+            head.insert("backtrace".to_owned(), bt.to_string().into());
+        }
+
+        serde_json::Value::Array(values)
+    }
+
+    /// Returns the backtrace provided by the error if it's captured.
+    #[cfg(all(feature = "backtrace", feature = "serde"))]
+    fn captured_backtrace(&self) -> Option<&std::backtrace::Backtrace> {
+        use std::backtrace::{Backtrace, BacktraceStatus};
+
+        std::error::request_ref::<Backtrace>(self.0)
+            .filter(|bt| bt.status() == BacktraceStatus::Captured)
+    }
+
+    /// Returns the backtraces to show in the [`fmt::Debug`] output. Each one
+    /// is labeled with the message of the error that captured it if all
+    /// backtraces are requested.
+    #[cfg(feature = "backtrace")]
+    fn backtraces_to_show(&self) -> Vec<(Option<String>, &'a std::backtrace::Backtrace)> {
+        use std::backtrace::{Backtrace, BacktraceStatus};
+
+        // Hack for testing purposes.
+        // Read the env var could be slow but we short-circuit it in release mode,
+        // so this should be optimized out in production.
+        let force_show_backtrace = cfg!(debug_assertions)
+            && std::env::var("THISERROR_EXT_TEST_SHOW_USELESS_BACKTRACE").is_ok();
+
+        // If the backtrace is disabled or unsupported, behave as if there's no backtrace.
+        let request = |error: &'a dyn std::error::Error| {
+            std::error::request_ref::<Backtrace>(error)
+                .filter(|bt| bt.status() == BacktraceStatus::Captured || force_show_backtrace)
+        };
+
+        if !self.1.all_backtraces {
+            return request(self.0).map(|bt| (None, bt)).into_iter().collect();
+        }
+
+        let messages: Vec<_> = if self.1.cleaning {
+            CleanedErrorText::new(self.0)
+                // Fall back to the original message if it's empty after cleaning.
+                .map(|(error, msg, _cleaned)| {
+                    let msg = if msg.is_empty() {
+                        error.to_string()
+                    } else {
+                        msg
+                    };
+                    (error, msg)
+                })
+                .collect()
+        } else {
+            uncleaned_messages(self.0).collect()
+        };
+
+        let mut backtraces: Vec<(Option<String>, &Backtrace)> = Vec::new();
+        for (error, message) in messages {
+            let Some(bt) = request(error) else { continue };
+            match backtraces.last_mut() {
+                // The backtrace is propagated from the source, attribute it to the source.
+                Some(last) if std::ptr::eq(last.1, bt) => *last = (Some(message), bt),
+                _ => backtraces.push((Some(message), bt)),
+            }
+        }
+        backtraces
+    }
+
+    /// Returns the messages of the error and its sources, cleaned if enabled,
+    /// skipping the empty ones.
+    fn visible_messages(&self) -> Vec<String> {
+        let messages: Vec<_> = if self.1.cleaning {
+            CleanedErrorText::new(self.0)
+                .map(|(_error, msg, _cleaned)| msg)
+                .collect()
+        } else {
+            uncleaned_messages(self.0)
+                .map(|(_error, msg)| msg)
+                .collect()
+        };
+
+        messages.into_iter().filter(|msg| !msg.is_empty()).collect()
+    }
+
+    /// Wraps the value to be displayed with the given style, if ANSI styling
+    /// is enabled.
+    fn paint<T>(&self, style: Style, value: T) -> Paint<T> {
+        #[cfg(feature = "ansi")]
+        let enabled = self.1.ansi
+            && std::env::var_os("NO_COLOR").map_or(true, |no_color| no_color.is_empty());
+        #[cfg(not(feature = "ansi"))]
+        let enabled = false;
+
+        #[cfg(feature = "color")]
+        let colored = self.1.colored;
+        #[cfg(not(feature = "color"))]
+        let colored = false;
+
+        Paint {
+            value,
+            escape_code: enabled.then(|| style.escape_code(colored)),
+        }
+    }
+
+    fn cleaned_error_trace(&self, f: &mut fmt::Formatter, pretty: bool) -> Result<(), fmt::Error> {
+        let cleaned_messages = self.visible_messages();
+
         let mut visible_messages = cleaned_messages.iter();
 
         let head = match visible_messages.next() {
@@ -226,31 +1076,81 @@ impl<'a> Report<'a> {
             None => return Ok(()),
         };
 
-        write!(f, "{}", head)?;
+        // The number of sources that are not shown due to the depth limit.
+        let source_count = cleaned_messages.len() - 1;
+        let omitted = match self.1.max_depth {
+            Some(max_depth) => source_count.saturating_sub(max_depth),
+            None => 0,
+        };
+        let visible_messages = visible_messages.take(source_count - omitted);
+
+        if self.1.numbered {
+            if source_count == 0 {
+                return write!(f, "{}", self.paint(Style::Head, head));
+            }
+
+            // No newline at the end.
+            let head = indent_continuation(head, "1: ".len());
+            write!(
+                f,
+                "{}: {}",
+                self.paint(Style::Index, 1),
+                self.paint(Style::Head, head)
+            )?;
+            for (i, msg) in visible_messages.enumerate() {
+                // The head message is numbered 1.
+                let i = i + 2;
+                let msg = indent_continuation(msg, format!("{}: ", i).len());
+                write!(f, "\n{}: {}", self.paint(Style::Index, i), msg)?;
+            }
+            if omitted > 0 {
+                let more = format!("... ({} more)", omitted);
+                write!(f, "\n{}", self.paint(Style::Dim, more))?;
+            }
+            return Ok(());
+        }
+
+        write!(f, "{}", self.paint(Style::Head, head))?;
 
         if pretty {
-            match cleaned_messages.len() {
-                0 | 1 => {}
-                2 => {
-                    writeln!(f, "\n\nCaused by:")?;
-                    writeln!(f, "  {}", visible_messages.next().unwrap())?;
+            match source_count {
+                0 => {}
+                1 => {
+                    writeln!(
+                        f,
+                        "\n\n{}",
+                        self.paint(Style::Header, &self.1.headers.single)
+                    )?;
+                    for msg in visible_messages {
+                        writeln!(f, "  {}", indent_continuation(msg, 2))?;
+                    }
                 }
                 _ => {
                     writeln!(
                         f,
-                        "\n\nCaused by these errors (recent errors listed first):"
+                        "\n\n{}",
+                        self.paint(Style::Header, &self.1.headers.multiple)
                     )?;
                     for (i, msg) in visible_messages.enumerate() {
                         // Let's use 1-based indexing for presentation
                         let i = i + 1;
-                        writeln!(f, "{:3}: {}", i, msg)?;
+                        let msg = indent_continuation(msg, format!("{:3}: ", i).len());
+                        writeln!(f, "{:3}: {}", self.paint(Style::Index, i), msg)?;
                     }
                 }
             }
+            if omitted > 0 {
+                let more = format!("... ({} more)", omitted);
+                writeln!(f, "  {}", self.paint(Style::Dim, more))?;
+            }
         } else {
             // No newline at the end.
             for msg in visible_messages {
-                write!(f, ": {}", msg)?;
+                write!(f, "{}{}", self.1.separator, msg)?;
+            }
+            if omitted > 0 {
+                let more = format!("... ({} more)", omitted);
+                write!(f, "{}{}", self.1.separator, self.paint(Style::Dim, more))?;
             }
         }
 
@@ -258,13 +1158,257 @@ impl<'a> Report<'a> {
     }
 }
 
+/// Indents the continuation lines of a multi-line message with the given number
+/// of spaces, so that they're aligned with the first line following a prefix of
+/// the same width. Empty lines are kept empty.
+fn indent_continuation(msg: &str, indent: usize) -> Cow<'_, str> {
+    if !msg.contains('\n') {
+        return Cow::Borrowed(msg);
+    }
+
+    let mut indented = String::with_capacity(msg.len());
+    for (i, line) in msg.split('\n').enumerate() {
+        if i > 0 {
+            indented.push('\n');
+            if !line.is_empty() {
+                indented.extend(std::iter::repeat(' ').take(indent));
+            }
+        }
+        indented.push_str(line);
+    }
+    Cow::Owned(indented)
+}
+
+/// The prefixes of the symbol names of backtrace frames that are usually noise.
+#[cfg(feature = "backtrace")]
+const NOISE_FRAME_PREFIXES: &[&str] = &[
+    "core::",
+    "<core::",
+    "alloc::",
+    "<alloc::",
+    "std::rt::",
+    "std::panic::",
+    "std::panicking::",
+    "std::sys::",
+    "std::sys_common::",
+    "std::backtrace::",
+    "std::backtrace_rs::",
+    "std::thread::",
+    "thiserror_ext::",
+    "<thiserror_ext::",
+    "rust_begin_unwind",
+    "__rust_",
+    "__libc_start",
+    "test::",
+    "<test::",
+];
+
+#[cfg(feature = "backtrace")]
+fn is_noise_frame(frame: &str) -> bool {
+    frame == "_start"
+        || frame == "main"
+        || NOISE_FRAME_PREFIXES
+            .iter()
+            .any(|prefix| frame.starts_with(prefix))
+}
+
+/// Filters the frames of the formatted backtrace with the given predicate on
+/// the symbol names. The lines following a frame, like the source location,
+/// are kept or removed along with the frame.
+#[cfg(feature = "backtrace")]
+fn filter_backtrace(backtrace: &str, filter: &dyn Fn(&str) -> bool) -> String {
+    let mut filtered = String::new();
+    let mut keep = true;
+
+    for line in backtrace.split_inclusive('\n') {
+        // A frame starts with a line like `  12: symbol::name`.
+        let symbol = line
+            .trim_start()
+            .split_once(": ")
+            .filter(|(index, _)| index.parse::<usize>().is_ok())
+            .map(|(_, symbol)| symbol.trim_end());
+        if let Some(symbol) = symbol {
+            keep = filter(symbol);
+        }
+        if keep {
+            filtered.push_str(line);
+        }
+    }
+
+    filtered
+}
+
+/// The styles used in the report when ANSI styling is enabled.
+#[derive(Clone, Copy)]
+enum Style {
+    /// The message of the error itself.
+    Head,
+    /// The headers preceding the sources.
+    Header,
+    /// The indices of the sources.
+    Index,
+    /// The indicator of omitted sources.
+    Dim,
+}
+
+impl Style {
+    fn escape_code(self, colored: bool) -> &'static str {
+        match (self, colored) {
+            (Style::Head, false) => "\x1b[1m",
+            (Style::Header | Style::Dim, false) => "\x1b[2m",
+            (Style::Index, false) => "\x1b[36m",
+            (Style::Head, true) => "\x1b[31m",
+            (Style::Header, true) => "\x1b[33m",
+            (Style::Index | Style::Dim, true) => "\x1b[90m",
+        }
+    }
+}
+
+/// A value to be displayed with an optional escape code.
+struct Paint<T> {
+    value: T,
+    escape_code: Option<&'static str>,
+}
+
+impl<T: fmt::Display> fmt::Display for Paint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.escape_code {
+            // Pass through the formatter so that the width of the index applies.
+            Some(escape_code) => {
+                f.write_str(escape_code)?;
+                self.value.fmt(f)?;
+                f.write_str("\x1b[0m")
+            }
+            None => self.value.fmt(f),
+        }
+    }
+}
+
+/// A wrapper around a [`Report`] that formats it with colors: the error
+/// itself in red, the source headers in yellow, and the source indices in grey.
+///
+/// Constructed using [`AsReport::as_colored_report`], or from a [`Report`] or
+/// a [`FormattedReport`] with customized options.
+///
+/// Colors are only applied if the standard error is a terminal, as reports are
+/// usually printed there. This can be overridden by setting the `NO_COLOR`
+/// environment variable to disable colors, or the `CLICOLOR_FORCE` environment
+/// variable to enable them, to a non-empty value. `NO_COLOR` takes precedence.
+///
+/// Same as [`Report`], the alternate flag (`#`) selects the multi-line format.
+///
+/// Requires the `color` feature.
+#[cfg(feature = "color")]
+pub struct ColoredReport<'a>(FormattedReport<'a>);
+
+#[cfg(feature = "color")]
+impl<'a> From<Report<'a>> for ColoredReport<'a> {
+    fn from(report: Report<'a>) -> Self {
+        Self(report.formatted())
+    }
+}
+
+#[cfg(feature = "color")]
+impl<'a> From<FormattedReport<'a>> for ColoredReport<'a> {
+    fn from(report: FormattedReport<'a>) -> Self {
+        Self(report)
+    }
+}
+
+#[cfg(feature = "color")]
+impl<'a> fmt::Display for ColoredReport<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use std::io::IsTerminal;
+
+        let force = std::env::var_os("CLICOLOR_FORCE").is_some_and(|force| !force.is_empty());
+        let options = Options {
+            // `NO_COLOR` is respected when painting.
+            ansi: force || std::io::stderr().is_terminal(),
+            colored: true,
+            ..self.0 .1.clone()
+        };
+
+        fmt::Display::fmt(&FormattedReport(self.0 .0, options), f)
+    }
+}
+
+/// An iterator over an error and its sources, starting from the error itself.
+///
+/// The iteration stops after a reasonable depth in case of circular source
+/// references. The length is counted once on construction.
+///
+/// Usually constructed with [`Report::chain`].
+#[derive(Clone)]
+pub struct Chain<'a> {
+    next: Option<&'a dyn std::error::Error>,
+    remaining: usize,
+}
+
+impl<'a> Chain<'a> {
+    /// Constructs the iterator.
+    pub fn new(error: &'a dyn std::error::Error) -> Self {
+        // The error itself is not counted in the depth.
+        let mut remaining = 1;
+        let mut next = error.source();
+        while let Some(source) = next {
+            if remaining == MAX_CHAIN_DEPTH + 1 {
+                break;
+            }
+            remaining += 1;
+            next = source.source();
+        }
+
+        Self {
+            next: Some(error),
+            remaining,
+        }
+    }
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a dyn std::error::Error;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let error = self.next.take()?;
+        self.remaining -= 1;
+        self.next = error.source().map(|e| e as _);
+        Some(error)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Chain<'_> {}
+
+impl std::iter::FusedIterator for Chain<'_> {}
+
 /// An iterator over an Error and its sources that removes duplicated
 /// text from the error display strings.
-struct CleanedErrorText<'a>(Option<CleanedErrorTextStep<'a>>);
+///
+/// Each item is a tuple of the original error, the cleaned display string,
+/// and whether the string has been cleaned. See the documentation for
+/// [`Report`] for how the cleaning works.
+///
+/// Note that the cleaned display string can be empty, typically when an error
+/// emulates `#[error(transparent)]` by formatting exactly its source. These
+/// items are still yielded with the `bool` set to `true`, while [`Report`]
+/// skips them when formatting.
+///
+/// The iteration stops after a reasonable depth in case of circular source
+/// references, with `(cycle detected or chain too deep)` appended to the
+/// message of the last error.
+///
+/// Usually constructed with [`Report::iter_cleaned`].
+pub struct CleanedErrorText<'a>(Option<CleanedErrorTextStep<'a>>);
 
 impl<'a> CleanedErrorText<'a> {
     /// Constructs the iterator.
-    fn new(error: &'a dyn std::error::Error) -> Self {
+    pub fn new(error: &'a dyn std::error::Error) -> Self {
         Self(Some(CleanedErrorTextStep::new(error)))
     }
 }
@@ -280,6 +1424,15 @@ impl<'a> Iterator for CleanedErrorText<'a> {
         let mut error_text = mem::take(&mut step.error_text);
 
         match step.error.source() {
+            // Stop here in case of circular source references, with the source
+            // not shown, thus not cleaned.
+            Some(_) if step.depth >= MAX_CHAIN_DEPTH => {
+                if !error_text.is_empty() {
+                    error_text.push(' ');
+                }
+                error_text.push_str(CHAIN_TOO_DEEP);
+                Some((step.error, error_text, false))
+            }
             Some(next_error) => {
                 let next_error_text = next_error.to_string();
 
@@ -287,13 +1440,26 @@ impl<'a> Iterator for CleanedErrorText<'a> {
                     .trim_end_matches(&next_error_text)
                     .trim_end()
                     .trim_end_matches(':');
+                // Also strip the source text at the front if the error is formatted
+                // like `"{source}: while doing X"`. Only a full leading occurrence
+                // followed by `": "` is stripped, so that occurrences in the middle
+                // of the message are kept.
+                let cleaned_text = if next_error_text.is_empty() {
+                    cleaned_text
+                } else {
+                    cleaned_text
+                        .strip_prefix(next_error_text.as_str())
+                        .and_then(|rest| rest.strip_prefix(": "))
+                        .filter(|rest| !rest.trim().is_empty())
+                        .unwrap_or(cleaned_text)
+                };
                 let cleaned = cleaned_text.len() != error_text.len();
-                let cleaned_len = cleaned_text.len();
-                error_text.truncate(cleaned_len);
+                error_text = cleaned_text.to_owned();
 
                 self.0 = Some(CleanedErrorTextStep {
                     error: next_error,
                     error_text: next_error_text,
+                    depth: step.depth + 1,
                 });
 
                 Some((step.error, error_text, cleaned))
@@ -306,11 +1472,17 @@ impl<'a> Iterator for CleanedErrorText<'a> {
 struct CleanedErrorTextStep<'a> {
     error: &'a dyn std::error::Error,
     error_text: String,
+    /// The number of sources walked through to reach this error.
+    depth: usize,
 }
 
 impl<'a> CleanedErrorTextStep<'a> {
     fn new(error: &'a dyn std::error::Error) -> Self {
         let error_text = error.to_string();
-        Self { error, error_text }
+        Self {
+            error,
+            error_text,
+            depth: 0,
+        }
     }
 }
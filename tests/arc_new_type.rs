@@ -21,3 +21,84 @@ fn test() {
     let source = error2.source().unwrap();
     assert_eq!(source.to_string(), "invalid digit found in string");
 }
+
+#[test]
+fn test_try_into_inner() {
+    let error = SharedMyError::foo("nope".parse::<i32>().unwrap_err(), "hello".to_owned());
+    let error2 = error.clone();
+
+    // Shared, so the error is handed back.
+    let error = error.try_into_inner().unwrap_err();
+    drop(error2);
+
+    // Uniquely owned now.
+    let MyErrorInner::Foo { foo: context, .. } = error.try_into_inner().unwrap();
+    assert_eq!(context, "hello");
+}
+
+#[test]
+fn test_try_into_inner_and_get_mut() {
+    let mut error = SharedMyError::foo("nope".parse::<i32>().unwrap_err(), "hello".to_owned());
+    let mut error2 = error.clone();
+
+    // Shared, so no exclusive access.
+    assert!(error.get_mut().is_none());
+    assert!(error2.get_mut().is_none());
+    let mut error = error.try_into_inner().unwrap_err();
+
+    drop(error2);
+
+    // Uniquely owned now.
+    let MyErrorInner::Foo { foo: context, .. } = error.get_mut().unwrap();
+    context.push_str(", world");
+    let MyErrorInner::Foo { foo: context, .. } = error.try_into_inner().unwrap();
+    assert_eq!(context, "hello, world");
+}
+
+// `map_cloned` requires the inner error to be `Clone`.
+#[derive(Error, Debug, Clone, Arc, Construct)]
+#[thiserror_ext(newtype(name = SharedCloneableError))]
+pub enum CloneableErrorInner {
+    #[error("foo: {context}")]
+    Foo {
+        source: ParseIntError,
+        context: String,
+    },
+}
+
+#[derive(Error, Debug, Arc)]
+#[thiserror_ext(newtype(name = SharedWrappedError))]
+pub enum WrappedErrorInner {
+    #[error("wrapped")]
+    Wrapped(#[source] CloneableErrorInner),
+}
+
+#[test]
+fn test_map_cloned() {
+    let error = SharedCloneableError::foo("nope".parse::<i32>().unwrap_err(), "hello".to_owned());
+
+    let wrapped = SharedWrappedError::from(error.0.map_cloned(WrappedErrorInner::Wrapped));
+    assert_eq!(
+        wrapped.to_report_string(),
+        "wrapped: foo: hello: invalid digit found in string"
+    );
+
+    // The original error is still available.
+    assert_eq!(error.to_string(), "foo: hello");
+}
+
+#[test]
+fn test_arc_as_ref() {
+    use std::borrow::Borrow;
+
+    fn context(error: impl AsRef<MyErrorInner>) -> String {
+        let MyErrorInner::Foo { foo, .. } = error.as_ref();
+        foo.clone()
+    }
+
+    let error = SharedMyError::foo("nope".parse::<i32>().unwrap_err(), "hello".to_owned());
+    assert_eq!(context(error.0.clone()), "hello");
+
+    let inner: &MyErrorInner = error.0.borrow();
+    assert!(std::ptr::eq(inner, error.inner()));
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use thiserror::Error;
+use thiserror_ext::{AsDyn, AsReport};
+
+#[derive(Error, Debug)]
+#[error("inner error")]
+struct Inner;
+
+#[derive(Error, Debug)]
+#[error("outer error")]
+struct Outer {
+    #[source]
+    inner: Inner,
+}
+
+fn source_of<E: AsDyn + ?Sized>(error: &E) -> Option<String> {
+    error.as_dyn().source().map(|s| s.to_string())
+}
+
+#[test]
+fn test_as_dyn_concrete() {
+    let error = Outer { inner: Inner };
+    assert_eq!(source_of(&error).as_deref(), Some("inner error"));
+}
+
+#[test]
+fn test_as_dyn_boxed() {
+    let error: Box<dyn std::error::Error + Send + Sync> = Box::new(Outer { inner: Inner });
+
+    // Smart pointers of trait objects are auto-dereferenced in method calls.
+    assert_eq!(error.as_dyn().to_string(), "outer error");
+    assert_eq!(error.as_dyn().source().unwrap().to_string(), "inner error");
+    assert_eq!(source_of(&*error).as_deref(), Some("inner error"));
+    assert_eq!(error.to_report_string(), "outer error: inner error");
+}
+
+#[test]
+fn test_as_dyn_arc() {
+    let error: Arc<dyn std::error::Error + Send + Sync> = Arc::new(Outer { inner: Inner });
+
+    assert_eq!(error.as_dyn().to_string(), "outer error");
+    // `Arc<dyn Error>` is itself an `Error`, so it can be passed directly.
+    assert_eq!(source_of(&error).as_deref(), Some("inner error"));
+    assert_eq!(error.to_report_string(), "outer error: inner error");
+}
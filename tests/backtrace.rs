@@ -5,6 +5,7 @@ use std::backtrace::Backtrace;
 
 use sealed_test::prelude::*;
 use thiserror::Error;
+use thiserror_ext::{AsReport, MaybeBacktrace, NoExtraBacktrace, WithBacktrace};
 use thiserror_ext_derive::Box;
 
 #[derive(Error, Debug)]
@@ -41,6 +42,29 @@ fn parse_float_with_backtrace(input: &str) -> Result<f32, MyError> {
     Ok(parse_inner(input)?) // already has backtrace, no need to capture
 }
 
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = MyAlwaysError, backtrace = always))]
+enum MyAlwaysErrorInner {
+    #[error("parse float with backtrace")]
+    ParseFloatWithBacktrace {
+        #[from]
+        #[backtrace]
+        source: ParseFloatErrorWithBacktrace,
+    },
+}
+
+fn parse_float_always_backtrace(input: &str) -> Result<f32, MyAlwaysError> {
+    fn parse_inner(input: &str) -> Result<f32, ParseFloatErrorWithBacktrace> {
+        Ok(input.parse()?) // backtrace captured here
+    }
+
+    fn convert(input: &str) -> Result<f32, MyAlwaysError> {
+        Ok(parse_inner(input)?) // backtrace captured here again
+    }
+
+    convert(input)
+}
+
 fn parse_int(input: &str) -> Result<i32, MyError> {
     fn parse_inner(input: &str) -> Result<i32, std::num::ParseIntError> {
         input.parse() // no backtrace captured here
@@ -68,3 +92,194 @@ fn test_without_source_backtrace() {
 
     assert!(!backtrace.contains("parse_inner"), "{backtrace}");
 }
+
+#[sealed_test(env = [("RUST_BACKTRACE", "1")])]
+fn test_always_backtrace() {
+    let error = parse_float_always_backtrace("not a number").unwrap_err();
+    let backtrace = std::error::request_ref::<Backtrace>(&error)
+        .unwrap()
+        .to_string();
+
+    // The backtrace captured at the conversion site takes precedence.
+    assert!(backtrace.contains("convert"), "{backtrace}");
+    assert!(!backtrace.contains("parse_inner"), "{backtrace}");
+
+    // The one provided by the inner error is still accessible.
+    let inner_backtrace = std::error::request_ref::<Backtrace>(error.inner())
+        .unwrap()
+        .to_string();
+    assert!(inner_backtrace.contains("parse_inner"), "{inner_backtrace}");
+}
+
+/// Returns the symbol names of the frames in the backtrace section of the report.
+fn frames_in_report(report: &str) -> Vec<String> {
+    let (_, backtrace) = report.split_once("Backtrace:\n").unwrap();
+    backtrace
+        .lines()
+        .filter_map(|line| {
+            let (index, symbol) = line.trim_start().split_once(": ")?;
+            index.parse::<usize>().ok()?;
+            Some(symbol.to_owned())
+        })
+        .collect()
+}
+
+#[sealed_test(env = [("RUST_BACKTRACE", "1")])]
+fn test_backtrace_filter() {
+    let error = parse_float_with_backtrace("not a number").unwrap_err();
+
+    let full = format!("{:?}", error.as_report());
+    let full_frames = frames_in_report(&full);
+    assert!(
+        full_frames.iter().any(|f| f.contains("parse_inner")),
+        "{full}"
+    );
+
+    let filtered = format!(
+        "{:?}",
+        error
+            .as_report()
+            .with_backtrace_filter(|frame| !frame.contains("parse_inner"))
+    );
+    let filtered_frames = frames_in_report(&filtered);
+    assert!(!filtered.contains("parse_inner"), "{filtered}");
+    let removed = full_frames
+        .iter()
+        .filter(|f| f.contains("parse_inner"))
+        .count();
+    assert_eq!(
+        filtered_frames.len() + removed,
+        full_frames.len(),
+        "{filtered}"
+    );
+    assert!(filtered.contains("invalid float literal"), "{filtered}");
+}
+
+#[sealed_test(env = [("RUST_BACKTRACE", "1")])]
+fn test_default_backtrace_filter() {
+    let error = parse_float_with_backtrace("not a number").unwrap_err();
+
+    let report = format!("{:?}", error.as_report().with_default_backtrace_filter());
+    let frames = frames_in_report(&report);
+
+    assert!(frames.iter().any(|f| f.contains("parse_inner")), "{report}");
+    assert!(
+        !frames
+            .iter()
+            .any(|f| f.starts_with("core::") || f.starts_with("std::rt::")),
+        "{report}"
+    );
+}
+
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = MyWrappedError, backtrace))]
+enum MyWrappedErrorInner {
+    #[error("wrapped")]
+    Wrapped(#[source] MyErrorInner),
+}
+
+#[sealed_test(env = [("RUST_BACKTRACE", "1")])]
+fn test_map_inner() {
+    let error = parse_int("not a number").unwrap_err();
+    let backtrace = std::error::request_ref::<Backtrace>(&error).unwrap() as *const Backtrace;
+
+    // The backtrace captured by `MyError` is kept after the transformation.
+    let wrapped = MyWrappedError::from(error.0.map_inner(MyWrappedErrorInner::Wrapped));
+    let wrapped_backtrace = std::error::request_ref::<Backtrace>(&wrapped).unwrap();
+    assert_eq!(wrapped_backtrace as *const Backtrace, backtrace);
+    assert_eq!(
+        wrapped.to_report_string(),
+        "wrapped: parse int: invalid digit found in string"
+    );
+}
+
+/// A user-defined wrapper attaching a tag to the error, reusing the backtrace
+/// capturing logic of the generated new types.
+struct Tagged<T, B> {
+    inner: T,
+    tag: &'static str,
+    backtrace: B,
+}
+
+impl<T: std::error::Error, B: WithBacktrace> Tagged<T, B> {
+    fn new(inner: T, tag: &'static str) -> Self {
+        let backtrace = B::capture(&inner);
+        Self {
+            inner,
+            tag,
+            backtrace,
+        }
+    }
+}
+
+impl<T: std::fmt::Debug, B> std::fmt::Debug for Tagged<T, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {:?}", self.tag, self.inner)
+    }
+}
+
+impl<T: std::fmt::Display, B> std::fmt::Display for Tagged<T, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.tag, self.inner)
+    }
+}
+
+impl<T: std::error::Error, B: WithBacktrace> std::error::Error for Tagged<T, B> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner.source()
+    }
+
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        self.backtrace.provide(request);
+        self.inner.provide(request);
+    }
+}
+
+#[sealed_test(env = [("RUST_BACKTRACE", "1")])]
+fn test_user_defined_wrapper() {
+    fn tag_int(input: &str) -> Tagged<std::num::ParseIntError, MaybeBacktrace> {
+        Tagged::new(input.parse::<i32>().unwrap_err(), "int")
+    }
+
+    fn tag_float(input: &str) -> Tagged<ParseFloatErrorWithBacktrace, MaybeBacktrace> {
+        fn parse_inner(input: &str) -> Result<f32, ParseFloatErrorWithBacktrace> {
+            Ok(input.parse()?) // backtrace captured here
+        }
+        Tagged::new(parse_inner(input).unwrap_err(), "float")
+    }
+
+    // The inner error has no backtrace, so it's captured by the wrapper.
+    let error = tag_int("not a number");
+    assert_eq!(error.to_string(), "[int] invalid digit found in string");
+    let backtrace = std::error::request_ref::<Backtrace>(&error)
+        .unwrap()
+        .to_string();
+    assert!(backtrace.contains("tag_int"), "{backtrace}");
+
+    // The inner error has a backtrace, which is provided instead.
+    let error = tag_float("not a number");
+    let backtrace = std::error::request_ref::<Backtrace>(&error)
+        .unwrap()
+        .to_string();
+    assert!(backtrace.contains("parse_inner"), "{backtrace}");
+
+    // No extra backtrace is captured.
+    let error: Tagged<_, NoExtraBacktrace> = Tagged::new("x".parse::<i32>().unwrap_err(), "int");
+    assert!(std::error::request_ref::<Backtrace>(&error).is_none());
+}
+
+/// An implementation written without the `backtrace` feature, which does not
+/// define `provide`.
+struct Untracked;
+
+impl WithBacktrace for Untracked {
+    fn capture(_inner: &dyn std::error::Error) -> Self {
+        Self
+    }
+}
+
+#[test]
+fn test_default_provide() {
+    let error: Tagged<_, Untracked> = Tagged::new("x".parse::<i32>().unwrap_err(), "int");
+    assert!(std::error::request_ref::<Backtrace>(&error).is_none());
+}
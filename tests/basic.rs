@@ -63,3 +63,25 @@ pub enum MyErrorBacktraceInner {
 
 #[test]
 fn test() {}
+
+#[test]
+fn test_box_as_ref() {
+    use std::borrow::Borrow;
+
+    fn unsupported(error: impl AsRef<MyErrorInner>) -> Option<String> {
+        match error.as_ref() {
+            MyErrorInner::UnsupportedSingleField(what) => Some(what.clone()),
+            _ => None,
+        }
+    }
+
+    let mut error = MyError::unsupported_single_field("foo");
+    assert_eq!(unsupported(&error.0).as_deref(), Some("foo"));
+
+    if let MyErrorInner::UnsupportedSingleField(what) = error.0.as_mut() {
+        what.push_str("bar");
+    }
+    let inner: &MyErrorInner = error.0.borrow();
+    assert_eq!(inner.to_string(), "unsupported: foobar");
+    assert!(std::ptr::eq(inner, error.inner()));
+}
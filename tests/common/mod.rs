@@ -0,0 +1,37 @@
+//! The error chain shared by the report tests.
+
+#![allow(dead_code)]
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("inner error")]
+pub struct Inner;
+
+#[derive(Error, Debug)]
+#[error("middle error: {source}")] // the suffix of the source message should be cleaned up
+pub struct Middle {
+    pub source: Inner,
+}
+
+#[derive(Error, Debug)]
+#[error("{source}")] // the whole message should be cleaned up, as it's empty after cleaning
+pub struct MiddleTransparent {
+    pub source: Middle,
+}
+
+#[derive(Error, Debug)]
+#[error("outer error")]
+pub struct Outer {
+    pub source: MiddleTransparent,
+}
+
+pub fn middle() -> Middle {
+    Middle { source: Inner }
+}
+
+pub fn outer() -> Outer {
+    Outer {
+        source: MiddleTransparent { source: middle() },
+    }
+}
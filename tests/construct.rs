@@ -0,0 +1,431 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use thiserror::Error;
+use thiserror_ext::{Box, Construct};
+
+#[derive(Error, Debug, Construct)]
+#[error("failed to read `{path}`")]
+struct ReadError {
+    path: String,
+    source: std::io::Error,
+}
+
+#[derive(Error, Debug, Construct)]
+#[error("invalid id {id}: {reason}")]
+struct InvalidId {
+    id: u64,
+    reason: String,
+}
+
+#[derive(Error, Debug, Construct)]
+#[error("unsupported: {0}")]
+struct Unsupported(String);
+
+#[derive(Error, Debug, Construct, Box)]
+#[thiserror_ext(newtype(name = BoxedError))]
+#[error("bad request: {message}")]
+struct BoxedErrorInner {
+    message: String,
+}
+
+#[test]
+fn test_struct_with_source() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+    let error = ReadError::new(io_error, "foo.txt");
+
+    assert_eq!(error.path, "foo.txt");
+    assert_eq!(error.source.kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(error.to_string(), "failed to read `foo.txt`");
+}
+
+#[test]
+fn test_struct_without_source() {
+    let error = InvalidId::new(42u32, "negative");
+
+    assert_eq!(error.id, 42);
+    assert_eq!(error.to_string(), "invalid id 42: negative");
+}
+
+#[test]
+fn test_tuple_struct() {
+    let error = Unsupported::new("foo");
+    assert_eq!(error.to_string(), "unsupported: foo");
+}
+
+#[test]
+fn test_struct_newtype() {
+    let error = BoxedError::new("oops");
+    assert_eq!(error.to_string(), "bad request: oops");
+}
+
+#[derive(Error, Debug, Construct)]
+enum RenamedError {
+    #[error("io error")]
+    #[construct(name = "io")]
+    IoError(#[source] std::io::Error),
+
+    #[error("parse error: {0}")]
+    ParseError(String),
+}
+
+#[test]
+fn test_renamed() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+    let error = RenamedError::io(io_error);
+    assert!(matches!(error, RenamedError::IoError(_)));
+
+    let error = RenamedError::parse_error("foo");
+    assert!(matches!(error, RenamedError::ParseError(_)));
+}
+
+#[derive(Error, Debug, Construct)]
+#[construct(name = "feature")]
+#[error("not implemented: {feature}")]
+struct NotImplemented {
+    feature: String,
+}
+
+#[test]
+fn test_struct_renamed() {
+    let error = NotImplemented::feature("foo");
+    assert_eq!(error.to_string(), "not implemented: foo");
+}
+
+#[derive(Error, Debug, Construct)]
+enum RequestError {
+    #[error("http error: {status}")]
+    #[construct(name = "http_error")]
+    HTTPError { status: u16 },
+
+    #[error("type mismatch")]
+    #[construct(name = "r#type")]
+    Type,
+
+    #[error("skipped")]
+    #[construct(skip, name = "skipped")]
+    Skipped,
+}
+
+impl RequestError {
+    // No conflict as the constructor is skipped.
+    fn skipped() -> Self {
+        Self::Skipped
+    }
+}
+
+#[test]
+fn test_renamed_override() {
+    let error = RequestError::http_error(404u16);
+    assert_eq!(error.to_string(), "http error: 404");
+
+    // Keywords are allowed as raw identifiers.
+    let error = RequestError::r#type();
+    assert_eq!(error.to_string(), "type mismatch");
+
+    let error = RequestError::skipped();
+    assert_eq!(error.to_string(), "skipped");
+}
+
+#[derive(Error, Debug, Construct)]
+enum RequestBuilderError {
+    #[error("failed to request `{url}`")]
+    #[construct(builder)]
+    Request {
+        #[source]
+        error: std::io::Error,
+        url: String,
+        method: Option<String>,
+        status: Option<u16>,
+        retries: Option<u32>,
+        #[construct(default)]
+        headers: Vec<String>,
+    },
+
+    #[error("timeout")]
+    Timeout,
+}
+
+#[derive(Error, Debug, Construct)]
+#[construct(builder)]
+#[error("invalid config `{key}`")]
+struct ConfigError {
+    key: String,
+    value: Option<String>,
+}
+
+#[test]
+fn test_builder() {
+    let io_error = || std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+
+    let RequestBuilderError::Request {
+        url,
+        method,
+        status,
+        retries,
+        headers,
+        ..
+    } = RequestBuilder::new(io_error(), "https://example.com")
+        .method("GET")
+        .status(404u16)
+        .retries(3u32)
+        .headers(vec!["accept".to_owned()])
+        .build()
+    else {
+        panic!("unexpected variant");
+    };
+    assert_eq!(url, "https://example.com");
+    assert_eq!(method.as_deref(), Some("GET"));
+    assert_eq!(status, Some(404));
+    assert_eq!(retries, Some(3));
+    assert_eq!(headers, ["accept"]);
+
+    // Partially specified.
+    let RequestBuilderError::Request {
+        method,
+        status,
+        retries,
+        headers,
+        ..
+    } = RequestBuilder::new(io_error(), "https://example.com")
+        .status(500u16)
+        .build()
+    else {
+        panic!("unexpected variant");
+    };
+    assert_eq!(method, None);
+    assert_eq!(status, Some(500));
+    assert_eq!(retries, None);
+    assert!(headers.is_empty());
+
+    // Other variants still get the flat constructors.
+    let error = RequestBuilderError::timeout();
+    assert!(matches!(error, RequestBuilderError::Timeout));
+}
+
+#[test]
+fn test_builder_struct() {
+    let error = ConfigErrorBuilder::new("foo").build();
+    assert_eq!(error.key, "foo");
+    assert_eq!(error.value, None);
+}
+
+mod visibility {
+    use super::*;
+
+    #[derive(Error, Debug, Construct)]
+    pub enum VisError {
+        #[error("public")]
+        Public,
+
+        #[error("internal")]
+        #[construct(vis = pub(crate))]
+        Internal,
+
+        #[error("private")]
+        #[construct(vis = "pub(super)")]
+        Private,
+    }
+}
+
+#[test]
+fn test_vis() {
+    use visibility::VisError;
+
+    assert!(matches!(VisError::public(), VisError::Public));
+    assert!(matches!(VisError::internal(), VisError::Internal));
+    assert!(matches!(VisError::private(), VisError::Private));
+}
+
+#[derive(Error, Debug, Construct)]
+enum GenericError<T: std::fmt::Debug> {
+    #[error("invalid value: {0:?}")]
+    Invalid(T),
+    #[error("missing")]
+    Missing,
+}
+
+#[derive(Error, Debug, Construct)]
+enum BorrowedError<'a> {
+    #[error("unknown key `{key}`")]
+    UnknownKey { key: &'a str },
+}
+
+#[derive(Error, Debug, Construct)]
+enum WhereError<I>
+where
+    I: Iterator,
+    I::Item: std::fmt::Debug,
+{
+    #[error("unexpected item: {item:?}")]
+    Unexpected { item: I::Item },
+}
+
+#[test]
+fn test_generics() {
+    let error = GenericError::<i32>::invalid(42);
+    assert_eq!(error.to_string(), "invalid value: 42");
+    let error = GenericError::<String>::missing();
+    assert_eq!(error.to_string(), "missing");
+
+    let key = String::from("foo");
+    let error = BorrowedError::unknown_key(key.as_str());
+    assert_eq!(error.to_string(), "unknown key `foo`");
+
+    let error = WhereError::<std::vec::IntoIter<u8>>::unexpected(1u8);
+    assert_eq!(error.to_string(), "unexpected item: 1");
+}
+
+#[derive(Error, Debug, Construct)]
+enum AliasError {
+    #[error("parse int error from `{from}`")]
+    #[construct(alias = "parse_int", alias = "int")]
+    ParseIntError {
+        source: std::num::ParseIntError,
+        from: String,
+    },
+
+    #[error("timeout after {0}s")]
+    #[construct(alias = "timed_out")]
+    Timeout(u64),
+}
+
+#[test]
+fn test_alias() {
+    let source = || "foo".parse::<i32>().unwrap_err();
+
+    let expected = AliasError::parse_int_error(source(), "foo").to_string();
+    assert_eq!(AliasError::parse_int(source(), "foo").to_string(), expected);
+    assert_eq!(AliasError::int(source(), "foo").to_string(), expected);
+
+    assert_eq!(
+        AliasError::timed_out(3u8).to_string(),
+        AliasError::timeout(3u8).to_string()
+    );
+}
+
+#[derive(Error, Debug, Construct)]
+enum ConstError {
+    #[error("timeout")]
+    #[construct(const)]
+    Timeout,
+
+    #[error("cancelled")]
+    #[construct(const, alias = "canceled")]
+    Cancelled {},
+}
+
+const TIMEOUT: ConstError = ConstError::timeout();
+const CANCELLED: ConstError = ConstError::cancelled();
+
+#[test]
+fn test_const() {
+    assert!(matches!(TIMEOUT, ConstError::Timeout));
+    assert!(matches!(CANCELLED, ConstError::Cancelled {}));
+    assert!(matches!(ConstError::canceled(), ConstError::Cancelled {}));
+}
+
+#[derive(Error, Debug)]
+#[error("io: {0}")]
+struct WrappedIoError(#[from] std::io::Error);
+
+#[derive(Error, Debug, Construct)]
+enum OptionalSourceError {
+    #[error("failed to load `{path}`")]
+    Load {
+        source: Option<WrappedIoError>,
+        path: String,
+    },
+}
+
+#[test]
+fn test_optional_source() {
+    use std::error::Error as _;
+
+    // Accepts anything convertible into the inner type of the source.
+    let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+    let error = OptionalSourceError::load(io_error, "config.toml");
+    assert_eq!(error.to_string(), "failed to load `config.toml`");
+    assert_eq!(error.source().unwrap().to_string(), "io: not found");
+
+    let error = OptionalSourceError::load_without_source("config.toml");
+    assert_eq!(error.to_string(), "failed to load `config.toml`");
+    assert!(error.source().is_none());
+}
+
+#[derive(Error, Debug, Construct)]
+enum ExactArgError {
+    #[error("invalid name `{name}` at {offset}")]
+    InvalidName {
+        #[construct(into = false)]
+        name: String,
+        offset: u64,
+    },
+
+    #[error("invalid config")]
+    #[construct(builder)]
+    InvalidConfig {
+        #[construct(into = false)]
+        key: Option<Box<str>>,
+    },
+}
+
+#[test]
+fn test_exact_arg() {
+    // The exact type of the argument is inferred, which would be ambiguous with
+    // `impl Into<String>`.
+    let error = ExactArgError::invalid_name("foo".into(), 42u32);
+    assert_eq!(error.to_string(), "invalid name `foo` at 42");
+    let error = ExactArgError::invalid_name(Default::default(), 0u8);
+    assert_eq!(error.to_string(), "invalid name `` at 0");
+
+    let error = InvalidConfigBuilder::new().key("foo".into()).build();
+    assert!(matches!(error, ExactArgError::InvalidConfig { key: Some(key) } if &*key == "foo"));
+}
+
+#[derive(Debug, PartialEq)]
+struct OutOfRange;
+
+impl From<std::num::TryFromIntError> for OutOfRange {
+    fn from(_: std::num::TryFromIntError) -> Self {
+        OutOfRange
+    }
+}
+
+#[derive(Error, Debug, Construct)]
+enum ValidatedError {
+    #[error("invalid port {port} of `{host}`")]
+    #[construct(try, alias = "bad_port")]
+    InvalidPort {
+        host: String,
+        port: std::num::NonZeroU16,
+    },
+
+    #[error("too many retries: {retries}")]
+    #[construct(try = OutOfRange)]
+    TooManyRetries {
+        retries: u8,
+        #[construct(into = false)]
+        fatal: bool,
+    },
+}
+
+#[test]
+fn test_try_ctor() {
+    let error = ValidatedError::invalid_port("localhost", 8080u16).unwrap();
+    assert_eq!(error.to_string(), "invalid port 8080 of `localhost`");
+    let error = ValidatedError::bad_port("localhost", 0u16).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "out of range integral type conversion attempted"
+    );
+
+    let error = ValidatedError::too_many_retries(3u64, true).unwrap();
+    assert!(matches!(
+        error,
+        ValidatedError::TooManyRetries { fatal: true, .. }
+    ));
+    assert_eq!(
+        ValidatedError::too_many_retries(u64::MAX, false).unwrap_err(),
+        OutOfRange
+    );
+}
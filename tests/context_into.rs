@@ -78,3 +78,213 @@ fn test_error_into_with() {
     let err: MyError = BarError.into_bar_with(|| ("hello", format!("wo{}", "rld")));
     expect!["hello && world: bar"].assert_eq(&err.to_report_string());
 }
+
+#[derive(Error, ContextInto, Debug)]
+#[error("{context}")]
+struct ContextError {
+    #[source]
+    error: FooError,
+    context: String,
+}
+
+#[test]
+fn test_struct_into() {
+    let err: ContextError = foo().into_context_error("hello").unwrap_err();
+    expect!["hello: foo"].assert_eq(&err.to_report_string());
+
+    let err: ContextError = foo()
+        .into_context_error_with(|| format!("wo{}", "rld"))
+        .unwrap_err();
+    expect!["world: foo"].assert_eq(&err.to_report_string());
+
+    let err: ContextError = FooError.into_context_error("hello");
+    expect!["hello: foo"].assert_eq(&err.to_report_string());
+}
+
+#[test]
+fn test_into_with_source() {
+    let err: MyError = foo()
+        .into_foo_with_source(|source| format!("{source:?} occurred"))
+        .unwrap_err();
+    expect!["FooError occurred: foo"].assert_eq(&err.to_report_string());
+
+    let err: MyError =
+        BarError.into_bar_with_source(|source| (format!("{source:?} occurred"), "world"));
+    expect!["BarError occurred && world: bar"].assert_eq(&err.to_report_string());
+}
+
+#[derive(Error, ContextInto, Debug)]
+#[error("{context}")]
+struct OptionalSourceError {
+    #[source]
+    error: Option<FooError>,
+    context: String,
+}
+
+#[test]
+fn test_optional_source() {
+    let err: OptionalSourceError = foo().into_optional_source_error("hello").unwrap_err();
+    assert!(err.error.is_some());
+    expect!["hello: foo"].assert_eq(&err.to_report_string());
+
+    let err: OptionalSourceError = FooError.into_optional_source_error_with(|| "hello");
+    assert!(err.error.is_some());
+
+    let err: OptionalSourceError = foo()
+        .into_optional_source_error_with_source(|source| format!("{source:?} occurred"))
+        .unwrap_err();
+    expect!["Some(FooError) occurred: foo"].assert_eq(&err.to_report_string());
+
+    let none: Option<FooError> = None;
+    let err: OptionalSourceError = none.into_optional_source_error("hello");
+    assert!(err.error.is_none());
+    expect!["hello"].assert_eq(&err.to_report_string());
+}
+
+#[derive(Error, ContextInto, Debug)]
+enum RenamedError {
+    #[error("failed to open `{path}`")]
+    #[context_into(name = "open_file")]
+    DBError {
+        #[source]
+        error: FooError,
+        path: String,
+    },
+}
+
+#[test]
+fn test_renamed() {
+    let err: RenamedError = FooError.into_open_file("a.txt");
+    expect!["failed to open `a.txt`: foo"].assert_eq(&err.to_report_string());
+
+    let err: RenamedError = foo().into_open_file("a.txt").unwrap_err();
+    expect!["failed to open `a.txt`: foo"].assert_eq(&err.to_report_string());
+
+    let err: RenamedError = foo().into_open_file_with(|| "b.txt").unwrap_err();
+    expect!["failed to open `b.txt`: foo"].assert_eq(&err.to_report_string());
+
+    let err: RenamedError = foo()
+        .into_open_file_with_source(|e| format!("{e}.txt"))
+        .unwrap_err();
+    expect!["failed to open `foo.txt`: foo"].assert_eq(&err.to_report_string());
+}
+
+// The extension trait is renamed as well, so `IntoDBError` is not generated.
+#[allow(unused_imports)]
+use IntoOpenFile as _;
+#[allow(dead_code)]
+trait IntoDBError {}
+
+mod no_with {
+    use super::*;
+
+    #[derive(Error, ContextInto, Debug)]
+    #[context_into(no_with)]
+    enum NoWithError {
+        #[error("{context}")]
+        Foo {
+            #[source]
+            error: FooError,
+            context: String,
+        },
+
+        #[error("{context}")]
+        #[context_into(with)]
+        Bar {
+            #[source]
+            error: BarError,
+            context: String,
+        },
+    }
+
+    // Would be ambiguous to call if `into_foo_with` were generated for `NoWithError`.
+    trait IntoFooWith {
+        fn into_foo_with(self, f: impl FnOnce() -> &'static str) -> String;
+    }
+
+    impl IntoFooWith for FooError {
+        fn into_foo_with(self, f: impl FnOnce() -> &'static str) -> String {
+            f().to_owned()
+        }
+    }
+
+    #[test]
+    fn test_no_with() {
+        let err: NoWithError = FooError.into_foo("hello");
+        expect!["hello: foo"].assert_eq(&err.to_report_string());
+        assert_eq!(FooError.into_foo_with(|| "hello"), "hello");
+
+        let err: NoWithError = bar().into_bar_with(|| "hello").unwrap_err();
+        expect!["hello: bar"].assert_eq(&err.to_report_string());
+    }
+}
+
+#[derive(Debug)]
+struct WrappedFooError(FooError);
+
+impl From<WrappedFooError> for FooError {
+    fn from(error: WrappedFooError) -> Self {
+        error.0
+    }
+}
+
+#[test]
+fn test_result_into_convertible() {
+    let wrapped = || Err::<(), _>(WrappedFooError(FooError));
+
+    let err: MyError = wrapped().into_foo("hello").unwrap_err();
+    expect!["hello: foo"].assert_eq(&err.to_report_string());
+
+    let err: MyError = wrapped().into_foo_with(|| "hello").unwrap_err();
+    expect!["hello: foo"].assert_eq(&err.to_report_string());
+
+    let err: MyError = wrapped()
+        .into_foo_with_source(|source| format!("{source:?} occurred"))
+        .unwrap_err();
+    expect!["FooError occurred: foo"].assert_eq(&err.to_report_string());
+}
+
+mod marker {
+    use super::*;
+
+    #[derive(Error, ContextInto, Debug)]
+    #[context_into(marker = "MarkedSource")]
+    enum MarkedError {
+        #[error("{context}")]
+        Foo { source: FooError, context: String },
+
+        #[error("{context}")]
+        OptionalFoo {
+            source: Option<FooError>,
+            context: String,
+        },
+    }
+
+    impl MarkedSource for WrappedFooError {}
+
+    #[test]
+    fn test_wrapper_into() {
+        let wrapped = || WrappedFooError(FooError);
+
+        let err: MarkedError = wrapped().into_foo("hello");
+        expect!["hello: foo"].assert_eq(&err.to_report_string());
+
+        let err: MarkedError = wrapped().into_foo_with(|| "hello");
+        expect!["hello: foo"].assert_eq(&err.to_report_string());
+
+        let err: MarkedError =
+            wrapped().into_foo_with_source(|source| format!("{source:?} occurred"));
+        expect!["FooError occurred: foo"].assert_eq(&err.to_report_string());
+
+        let err: MarkedError = wrapped().into_optional_foo("hello");
+        expect!["hello: foo"].assert_eq(&err.to_report_string());
+
+        // The existing implementations are not affected.
+        let err: MarkedError = FooError.into_foo("hello");
+        expect!["hello: foo"].assert_eq(&err.to_report_string());
+        let err: MarkedError = Err::<(), _>(wrapped()).into_foo("hello").unwrap_err();
+        expect!["hello: foo"].assert_eq(&err.to_report_string());
+        let err: MarkedError = None::<FooError>.into_optional_foo("hello");
+        expect!["hello"].assert_eq(&err.to_report_string());
+    }
+}
@@ -29,11 +29,29 @@ pub mod inner {
         },
 
         #[error("quux {message}")]
+        #[thiserror_ext(macro(doc = "Used when 1 + 1 is not 3."))]
         Quux { message: String },
+
+        #[error("corge {message}")]
+        Corge {
+            message: std::borrow::Cow<'static, str>,
+        },
+
+        #[error("grault {description}: {message}")]
+        Grault {
+            message: String,
+            #[message]
+            description: String,
+        },
     }
     #[derive(Error, Debug, Macro)]
     #[error("not implemented: {message}, issue: {issue:?}")]
-    #[thiserror_ext(macro(mangle, path = "crate::inner", vis = pub(super)))]
+    #[thiserror_ext(macro(
+        mangle,
+        path = "crate::inner",
+        vis = pub(super),
+        doc = "Used for features not implemented yet."
+    ))]
     pub struct NotImplemented {
         pub issue: Option<i32>,
         pub message: String,
@@ -85,6 +103,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_message_attr_precedence() {
+        use crate::inner::grault;
+
+        // `#[message]` takes precedence over the field named `message`.
+        let a = grault!(message = "world", "hello {}", 42);
+        assert!(matches!(
+            a.inner(),
+            MyError::Grault { message, description } if message == "world" && description == "hello 42"
+        ));
+    }
+
     #[test]
     fn test_bail() {
         use crate::inner::bail_quux;
@@ -103,6 +133,71 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_cow_message() {
+        use crate::inner::corge;
+        use std::borrow::Cow;
+
+        let a = corge!("hello");
+        assert!(matches!(
+            a.inner(),
+            MyError::Corge {
+                message: Cow::Borrowed("hello")
+            }
+        ));
+
+        let a = corge!("hello {}", 42);
+        assert!(matches!(
+            a.inner(),
+            MyError::Corge { message: Cow::Owned(message) } if message == "hello 42"
+        ));
+
+        // Inline arguments and escaped braces are still formatted.
+        let name = "world";
+        let a = corge!("hello {name}");
+        assert!(matches!(
+            a.inner(),
+            MyError::Corge { message: Cow::Owned(message) } if message == "hello world"
+        ));
+        let a = corge!("hello {{}}");
+        assert!(matches!(
+            a.inner(),
+            MyError::Corge { message } if message == "hello {}"
+        ));
+    }
+
+    #[test]
+    fn test_ensure() {
+        use crate::inner::{ensure_bar, ensure_quux};
+
+        fn test(n: i32) -> Result<(), anyhow::Error> {
+            ensure_quux!(n != 3, "{} is not allowed", n);
+            ensure_bar!(n < 10, issue = 42, "{} is too large", n);
+            ensure_quux!(n > 0);
+            Ok(())
+        }
+
+        test(1).unwrap();
+
+        let error = test(3).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BoxMyError>().unwrap().inner(),
+            MyError::Quux { message } if message == "3 is not allowed"
+        ));
+
+        let error = test(11).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BoxMyError>().unwrap().inner(),
+            MyError::Bar { issue: Some(42), message } if message == "11 is too large"
+        ));
+
+        let error = test(-1).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BoxMyError>().unwrap().inner(),
+            MyError::Quux { message } if message == "condition failed: `n > 0`"
+        ));
+    }
+
     #[test]
     fn test_struct() {
         use crate::inner::bail_not_implemented;
@@ -139,3 +234,158 @@ mod tests {
         ));
     }
 }
+
+mod no_bail {
+    use thiserror::Error;
+    use thiserror_ext_derive::Macro;
+
+    #[derive(Error, Debug, Macro)]
+    #[thiserror_ext(macro(no_bail))]
+    enum MyError {
+        #[error("grault {message}")]
+        Grault { message: String },
+    }
+
+    // Would conflict with the `bail_grault` and `ensure_grault` macros if they
+    // were generated.
+    #[allow(unused_imports)]
+    use std::{println as bail_grault, println as ensure_grault};
+
+    #[test]
+    fn test_no_bail() {
+        let error: MyError = grault!("oops");
+        assert_eq!(error.to_string(), "grault oops");
+    }
+}
+
+mod prefix {
+    use thiserror::Error;
+    use thiserror_ext_derive::Macro;
+
+    #[derive(Error, Debug, Macro)]
+    #[thiserror_ext(macro(prefix = "db_"))]
+    enum DbError {
+        #[error("db not found: {message}")]
+        NotFound { message: String },
+    }
+
+    #[derive(Error, Debug, Macro)]
+    #[thiserror_ext(macro(prefix = "fs_", mangle))]
+    enum FsError {
+        #[error("fs not found: {message}")]
+        NotFound { message: String },
+    }
+
+    #[test]
+    fn test_prefix() {
+        let error: DbError = db_not_found!("table {}", "t");
+        assert_eq!(error.to_string(), "db not found: table t");
+
+        let error: FsError = fs_not_found!("file {}", "f");
+        assert_eq!(error.to_string(), "fs not found: file f");
+
+        fn f() -> Result<(), DbError> {
+            db_bail_not_found!("bailed");
+        }
+        assert_eq!(f().unwrap_err().to_string(), "db not found: bailed");
+
+        fn g() -> Result<(), FsError> {
+            fs_ensure_not_found!(false, "ensured");
+            Ok(())
+        }
+        assert_eq!(g().unwrap_err().to_string(), "fs not found: ensured");
+    }
+}
+
+mod skip {
+    use thiserror::Error;
+    use thiserror_ext_derive::Macro;
+
+    // Variants without a `message` field must be explicitly skipped, otherwise
+    // a warning is emitted, which is denied in CI.
+    #[derive(Error, Debug, Macro)]
+    enum MyError {
+        #[error("garply {message}")]
+        Garply { message: String },
+
+        #[error("waldo {message}")]
+        #[thiserror_ext(macro(skip))]
+        Waldo { message: String },
+
+        #[error("fred")]
+        #[thiserror_ext(macro(skip))]
+        Fred,
+    }
+
+    // Would conflict with the `waldo` macro if it were generated.
+    #[allow(unused_imports)]
+    use std::println as waldo;
+
+    #[test]
+    fn test_skip() {
+        let error: MyError = garply!("oops");
+        assert_eq!(error.to_string(), "garply oops");
+
+        let error = MyError::Waldo {
+            message: "oops".to_owned(),
+        };
+        assert_eq!(error.to_string(), "waldo oops");
+        assert_eq!(MyError::Fred.to_string(), "fred");
+    }
+}
+
+mod location {
+    use std::panic::Location;
+
+    use thiserror::Error;
+    use thiserror_ext_derive::Macro;
+
+    #[derive(Error, Debug, Macro)]
+    #[thiserror_ext(macro(location))]
+    enum MyError {
+        #[error("xyzzy {message}")]
+        Xyzzy {
+            message: String,
+            #[location]
+            location: &'static Location<'static>,
+        },
+
+        #[error("thud {message}")]
+        Thud {
+            message: String,
+            #[location]
+            location: Location<'static>,
+        },
+    }
+
+    #[test]
+    fn test_location() {
+        let line = line!() + 1;
+        let error: MyError = xyzzy!("oops");
+        let MyError::Xyzzy { location, .. } = error else {
+            unreachable!()
+        };
+        assert_eq!(location.line(), line);
+        assert!(location.file().ends_with("macro.rs"));
+
+        let line = line!() + 1;
+        let error: MyError = thud!("oops");
+        let MyError::Thud { location, .. } = error else {
+            unreachable!()
+        };
+        assert_eq!(location.line(), line);
+    }
+
+    #[test]
+    fn test_location_bail() {
+        fn test() -> Result<(), MyError> {
+            bail_xyzzy!("oops");
+        }
+
+        let line = line!() - 3;
+        let MyError::Xyzzy { location, .. } = test().unwrap_err() else {
+            unreachable!()
+        };
+        assert_eq!(location.line(), line);
+    }
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "tracing")]
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use std::sync::{Arc, Mutex};
+
+use thiserror::Error;
+use thiserror_ext::{Box, Macro};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+#[derive(Error, Debug, Macro, Box)]
+#[thiserror_ext(newtype(name = MyError), macro(log))]
+enum MyErrorKind {
+    #[error("internal error: {message}")]
+    Internal { message: String },
+
+    #[error("invalid input `{input}`: {message}")]
+    Invalid { input: String, message: String },
+}
+
+#[derive(Error, Debug, Macro)]
+#[error("timeout: {message}")]
+#[thiserror_ext(macro(log = "warn"))]
+struct Timeout {
+    #[source]
+    source: Option<std::io::Error>,
+    message: String,
+}
+
+/// Records the level and the `error` field of the events.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<(Level, String)>>>);
+
+impl<S: Subscriber> Layer<S> for Recorder {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        struct ErrorVisitor(Option<String>);
+
+        impl Visit for ErrorVisitor {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "error" {
+                    self.0 = Some(format!("{:?}", value));
+                }
+            }
+        }
+
+        let mut visitor = ErrorVisitor(None);
+        event.record(&mut visitor);
+        if let Some(error) = visitor.0 {
+            let level = *event.metadata().level();
+            self.0.lock().unwrap().push((level, error));
+        }
+    }
+}
+
+fn record(f: impl FnOnce()) -> Vec<(Level, String)> {
+    let recorder = Recorder::default();
+    let subscriber = tracing_subscriber::registry().with(recorder.clone());
+    tracing::subscriber::with_default(subscriber, f);
+    let events = recorder.0.lock().unwrap().clone();
+    events
+}
+
+#[test]
+fn test_log() {
+    let events = record(|| {
+        let error: MyError = log_internal!("{} went wrong", 42);
+        assert_eq!(error.to_string(), "internal error: 42 went wrong");
+
+        let error = log_invalid!(input = "foo", "bad");
+        assert_eq!(error.to_string(), "invalid input `foo`: bad");
+    });
+
+    assert_eq!(
+        events,
+        vec![
+            (Level::ERROR, "internal error: 42 went wrong".to_owned()),
+            (Level::ERROR, "invalid input `foo`: bad".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_log_level() {
+    let events = record(|| {
+        let io_error = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        let error: Timeout = log_timeout!(source = io_error, "after {}s", 5);
+        assert_eq!(error.to_string(), "timeout: after 5s");
+    });
+
+    // The error is logged as a report, including the sources.
+    assert_eq!(
+        events,
+        vec![(Level::WARN, "timeout: after 5s: timed out".to_owned())]
+    );
+}
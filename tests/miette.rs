@@ -0,0 +1,93 @@
+#![cfg(feature = "miette")]
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use miette::{Diagnostic, Severity};
+use thiserror::Error;
+use thiserror_ext::{Arc, Box};
+
+#[derive(Error, Debug, Diagnostic, Box)]
+#[thiserror_ext(newtype(name = ParseError, diagnostic))]
+enum ParseErrorKind {
+    #[error("unexpected token")]
+    #[diagnostic(
+        code(parse::unexpected_token),
+        help("remove the token"),
+        severity(Warning)
+    )]
+    UnexpectedToken {
+        #[source_code]
+        src: String,
+        #[label("here")]
+        span: (usize, usize),
+    },
+}
+
+#[derive(Error, Debug, Diagnostic, Arc)]
+#[thiserror_ext(newtype(name = SharedError, diagnostic))]
+enum SharedErrorKind {
+    #[error("not found")]
+    #[diagnostic(code(shared::not_found), url("https://example.com"))]
+    NotFound,
+}
+
+// `Diagnostic` is not implemented unless opted in, even if the inner error
+// implements it.
+#[derive(Error, Debug, Diagnostic, Box)]
+#[thiserror_ext(newtype(name = PlainError))]
+enum PlainErrorKind {
+    #[error("plain")]
+    #[diagnostic(code(plain))]
+    Plain,
+}
+
+// Would be ambiguous with `Diagnostic::code` if it were implemented.
+trait Code {
+    fn code(&self) -> &'static str;
+}
+
+impl Code for PlainError {
+    fn code(&self) -> &'static str {
+        "not implemented"
+    }
+}
+
+#[test]
+fn test_diagnostic_box() {
+    let error: ParseError = ParseErrorKind::UnexpectedToken {
+        src: "let x = ;".to_owned(),
+        span: (8, 1),
+    }
+    .into();
+
+    assert_eq!(error.code().unwrap().to_string(), "parse::unexpected_token");
+    assert_eq!(error.help().unwrap().to_string(), "remove the token");
+    assert_eq!(error.severity(), Some(Severity::Warning));
+    assert!(error.source_code().is_some());
+
+    let labels: Vec<_> = error.labels().unwrap().collect();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].label(), Some("here"));
+    assert_eq!(labels[0].offset(), 8);
+
+    // Also usable as a `miette::Report`.
+    let report = miette::Report::new(error);
+    assert_eq!(
+        report.code().unwrap().to_string(),
+        "parse::unexpected_token"
+    );
+}
+
+#[test]
+fn test_diagnostic_arc() {
+    let error: SharedError = SharedErrorKind::NotFound.into();
+    assert_eq!(error.code().unwrap().to_string(), "shared::not_found");
+    assert_eq!(error.url().unwrap().to_string(), "https://example.com");
+    assert!(error.help().is_none());
+}
+
+#[test]
+fn test_no_diagnostic() {
+    let error: PlainError = PlainErrorKind::Plain.into();
+    assert_eq!(error.to_string(), "plain");
+    assert_eq!(error.code(), "not implemented");
+}
@@ -0,0 +1,88 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use thiserror::Error;
+use thiserror_ext::{Arc, Box};
+
+#[derive(Error, Debug)]
+#[error("io error")]
+struct IoError;
+
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = BoxedError, variant_accessors))]
+enum BoxedErrorKind {
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("timeout after {seconds}s")]
+    Timeout { seconds: u64 },
+    #[error("internal error")]
+    Internal,
+    #[error("invalid range: {0}..{1}")]
+    InvalidRange(u32, u32),
+    #[error(transparent)]
+    Io(#[from] IoError),
+}
+
+#[derive(Error, Debug, Arc)]
+#[thiserror_ext(newtype(name = SharedError, variant_accessors))]
+enum SharedErrorKind {
+    #[error("not found")]
+    NotFound,
+    #[error("invalid input")]
+    InvalidInput,
+}
+
+#[test]
+fn test_box_variant_accessors() {
+    let error: BoxedError = BoxedErrorKind::NotFound("foo".to_owned()).into();
+    assert!(error.is_not_found());
+    assert!(!error.is_timeout());
+    assert!(!error.is_internal());
+
+    let error: BoxedError = BoxedErrorKind::Timeout { seconds: 1 }.into();
+    assert!(!error.is_not_found());
+    assert!(error.is_timeout());
+
+    let error: BoxedError = BoxedErrorKind::Internal.into();
+    assert!(error.is_internal());
+
+    let error: BoxedError = IoError.into();
+    assert!(!error.is_not_found());
+    assert!(!error.is_timeout());
+    assert!(!error.is_internal());
+}
+
+#[test]
+fn test_arc_variant_accessors() {
+    let error: SharedError = SharedErrorKind::InvalidInput.into();
+    assert!(!error.is_not_found());
+    assert!(error.is_invalid_input());
+
+    let error: SharedError = SharedErrorKind::NotFound.into();
+    assert!(error.is_not_found());
+    assert!(!error.is_invalid_input());
+}
+
+#[test]
+fn test_box_as_accessors() {
+    let error: BoxedError = BoxedErrorKind::NotFound("foo".to_owned()).into();
+    assert_eq!(error.as_not_found().unwrap(), "foo");
+    assert!(error.as_timeout().is_none());
+    assert!(error.as_internal().is_none());
+
+    let error: BoxedError = BoxedErrorKind::Timeout { seconds: 1 }.into();
+    let TimeoutRef { seconds } = error.as_timeout().unwrap();
+    assert_eq!(*seconds, 1);
+    assert!(error.as_not_found().is_none());
+
+    let error: BoxedError = BoxedErrorKind::Internal.into();
+    assert_eq!(error.as_internal(), Some(()));
+
+    let error: BoxedError = BoxedErrorKind::InvalidRange(1, 2).into();
+    let (start, end) = error.as_invalid_range().unwrap();
+    assert_eq!((*start, *end), (1, 2));
+    assert!(error.is_invalid_range());
+
+    let error: BoxedError = IoError.into();
+    assert!(error.as_not_found().is_none());
+    assert!(error.as_internal().is_none());
+}
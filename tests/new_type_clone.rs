@@ -0,0 +1,45 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use thiserror::Error;
+use thiserror_ext::Box;
+
+#[derive(Error, Debug, Clone, PartialEq, Box)]
+#[thiserror_ext(newtype(name = MyError, clone, eq))]
+enum MyErrorKind {
+    #[error("not found: {0}")]
+    NotFound(String),
+}
+
+#[test]
+fn test_clone() {
+    let error: MyError = MyErrorKind::NotFound("foo".to_owned()).into();
+    let cloned = error.clone();
+    assert_eq!(error, cloned);
+
+    // The inner error is deeply cloned.
+    assert!(!std::ptr::eq(error.inner(), cloned.inner()));
+
+    let MyErrorKind::NotFound(mut name) = cloned.into_inner();
+    name.push_str("bar");
+    assert_eq!(name, "foobar");
+    assert_eq!(error.to_string(), "not found: foo");
+}
+
+#[cfg(feature = "backtrace")]
+#[test]
+fn test_clone_backtrace() {
+    use std::backtrace::Backtrace;
+
+    #[derive(Error, Debug, Clone, Box)]
+    #[thiserror_ext(newtype(name = MyBacktraceError, clone, backtrace))]
+    #[error("oops")]
+    struct MyBacktraceErrorKind;
+
+    let error: MyBacktraceError = MyBacktraceErrorKind.into();
+    let cloned = error.clone();
+
+    // The backtrace is shared.
+    let backtrace = std::error::request_ref::<Backtrace>(&error).unwrap();
+    let cloned_backtrace = std::error::request_ref::<Backtrace>(&cloned).unwrap();
+    assert!(std::ptr::eq(backtrace, cloned_backtrace));
+}
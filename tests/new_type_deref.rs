@@ -0,0 +1,52 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use thiserror::Error;
+use thiserror_ext::{Arc, Box};
+
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = BoxedError, deref))]
+enum BoxedErrorKind {
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("timeout after {secs}s")]
+    Timeout { secs: u64 },
+}
+
+impl BoxedErrorKind {
+    fn is_retryable(&self) -> bool {
+        matches!(self, Self::Timeout { .. })
+    }
+}
+
+#[derive(Error, Debug, Arc)]
+#[thiserror_ext(newtype(name = SharedError, deref))]
+#[error("shared error: {message}")]
+struct SharedErrorInner {
+    message: String,
+}
+
+#[test]
+fn test_box_deref() {
+    let error: BoxedError = BoxedErrorKind::Timeout { secs: 5 }.into();
+    assert!(error.is_retryable());
+
+    match &*error {
+        BoxedErrorKind::Timeout { secs } => assert_eq!(*secs, 5),
+        BoxedErrorKind::NotFound(_) => unreachable!(),
+    }
+
+    let error: BoxedError = BoxedErrorKind::NotFound("foo".to_owned()).into();
+    assert!(!error.is_retryable());
+    assert_eq!(error.to_string(), "not found: foo");
+}
+
+#[test]
+fn test_arc_deref() {
+    let error: SharedError = SharedErrorInner {
+        message: "foo".to_owned(),
+    }
+    .into();
+
+    assert_eq!(error.message, "foo");
+    assert_eq!(error.clone().message, "foo");
+}
@@ -0,0 +1,70 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use thiserror::Error;
+use thiserror_ext::Box;
+
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = MyError, downcast))]
+enum MyErrorInner {
+    #[error("cannot parse int from `{from}`")]
+    Parse {
+        #[source]
+        error: std::num::ParseIntError,
+        from: String,
+    },
+
+    #[error(transparent)]
+    IoTransparent(std::io::Error),
+}
+
+#[test]
+fn test_downcast_ref() {
+    use std::io::ErrorKind;
+
+    // Transparent, so the `io::Error` is not exposed as a source.
+    let error: MyError =
+        MyErrorInner::IoTransparent(std::io::Error::new(ErrorKind::Other, "oops")).into();
+    assert!(std::error::Error::source(&error).is_none());
+    let io_error = error.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(io_error.kind(), ErrorKind::Other);
+    assert!(error.downcast_ref::<std::num::ParseIntError>().is_none());
+
+    // Found in the fields.
+    let error: MyError = MyErrorInner::Parse {
+        error: "nope".parse::<i32>().unwrap_err(),
+        from: "nope".to_owned(),
+    }
+    .into();
+    assert!(error.downcast_ref::<std::num::ParseIntError>().is_some());
+    assert!(error.downcast_ref::<std::io::Error>().is_none());
+
+    // The inner error itself.
+    assert!(error.downcast_ref::<MyErrorInner>().is_some());
+}
+
+mod no_downcast {
+    use super::*;
+
+    #[derive(Error, Debug, Box)]
+    #[thiserror_ext(newtype(name = PlainError))]
+    #[error("plain")]
+    struct PlainErrorInner;
+
+    // Would be shadowed by the inherent method if `downcast_ref` were generated
+    // without `downcast`.
+    trait DowncastRef {
+        fn downcast_ref(&self) -> &'static str;
+    }
+
+    impl DowncastRef for PlainError {
+        fn downcast_ref(&self) -> &'static str {
+            "not generated"
+        }
+    }
+
+    #[test]
+    fn test_no_downcast_ref() {
+        let error: PlainError = PlainErrorInner.into();
+        assert_eq!(error.downcast_ref(), "not generated");
+    }
+}
@@ -0,0 +1,75 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+use thiserror_ext::{Arc, Box};
+
+#[derive(Error, Debug, PartialEq, Eq, Hash, Box)]
+#[thiserror_ext(newtype(name = BoxedError, eq, hash))]
+enum BoxedErrorKind {
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("timeout")]
+    Timeout,
+}
+
+#[derive(Error, Debug, PartialEq, Eq, Hash, Arc)]
+#[thiserror_ext(newtype(name = SharedError, eq, hash))]
+enum SharedErrorKind {
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("timeout")]
+    Timeout,
+}
+
+#[test]
+fn test_box_eq() {
+    let error: BoxedError = BoxedErrorKind::NotFound("foo".to_owned()).into();
+
+    assert_eq!(error, BoxedErrorKind::NotFound("foo".to_owned()).into());
+    assert_ne!(error, BoxedErrorKind::NotFound("bar".to_owned()).into());
+    assert_ne!(error, BoxedErrorKind::Timeout.into());
+}
+
+#[test]
+fn test_arc_eq() {
+    let error: SharedError = SharedErrorKind::NotFound("foo".to_owned()).into();
+
+    assert_eq!(error, error.clone());
+    assert_eq!(error, SharedErrorKind::NotFound("foo".to_owned()).into());
+    assert_ne!(error, SharedErrorKind::Timeout.into());
+}
+
+#[derive(Error, Debug, PartialEq, Box)]
+#[thiserror_ext(newtype(name = FloatError, eq))]
+enum FloatErrorKind {
+    #[error("not a number: {0}")]
+    NotANumber(f64),
+}
+
+fn assert_eq_impl<T: Eq>() {}
+
+#[test]
+fn test_eq() {
+    assert_eq_impl::<BoxedError>();
+    assert_eq_impl::<SharedError>();
+
+    // `FloatError` is not `Eq` as the inner type is not, but still `PartialEq`.
+    let error: FloatError = FloatErrorKind::NotANumber(1.0).into();
+    assert_eq!(error, FloatErrorKind::NotANumber(1.0).into());
+}
+
+#[test]
+fn test_hash() {
+    let mut set = HashSet::new();
+    set.insert(BoxedError::from(BoxedErrorKind::NotFound("foo".to_owned())));
+    set.insert(BoxedErrorKind::NotFound("foo".to_owned()).into());
+    set.insert(BoxedErrorKind::Timeout.into());
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&BoxedErrorKind::Timeout.into()));
+
+    let error: SharedError = SharedErrorKind::Timeout.into();
+    let set: HashSet<_> = [error.clone(), error, SharedErrorKind::Timeout.into()].into();
+    assert_eq!(set.len(), 1);
+}
@@ -0,0 +1,98 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use std::fmt::{Debug, Display};
+
+use thiserror::Error;
+use thiserror_ext::{Arc, Box, Construct};
+
+#[derive(Error, Debug, Construct, Box)]
+#[thiserror_ext(newtype(name = BoxParseError, eq, downcast))]
+enum ParseErrorInner<E: Debug + Display + Send + Sync + 'static> {
+    #[error("invalid value `{value}`")]
+    Invalid { value: E },
+
+    #[error("missing value")]
+    Missing,
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl<E: Debug + Display + Send + Sync + PartialEq + 'static> PartialEq for ParseErrorInner<E> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Invalid { value: a }, Self::Invalid { value: b }) => a == b,
+            (Self::Missing, Self::Missing) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Error, Debug, Construct, Arc)]
+#[thiserror_ext(newtype(name = ArcLookupError))]
+enum LookupErrorInner<K>
+where
+    K: Debug + Display,
+{
+    #[error("key `{0}` not found")]
+    NotFound(K),
+}
+
+#[test]
+fn test_box_generic() {
+    let error = BoxParseError::invalid(42);
+    assert_eq!(error.to_string(), "invalid value `42`");
+    assert!(matches!(
+        error.inner(),
+        ParseErrorInner::Invalid { value: 42 }
+    ));
+    assert!(matches!(
+        error.into_inner(),
+        ParseErrorInner::Invalid { value: 42 }
+    ));
+
+    let error: BoxParseError<String> = BoxParseError::missing();
+    assert_eq!(error, BoxParseError::missing());
+    assert_ne!(error, BoxParseError::invalid("foo"));
+}
+
+#[test]
+fn test_box_generic_from() {
+    fn parse(s: &str) -> Result<i32, BoxParseError<String>> {
+        if s.is_empty() {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "eof",
+            ))?;
+        }
+        s.parse().map_err(|_| BoxParseError::invalid(s))
+    }
+
+    assert_eq!(parse("42").unwrap(), 42);
+    assert_eq!(parse("foo").unwrap_err().to_string(), "invalid value `foo`");
+
+    let error = parse("").unwrap_err();
+    assert_eq!(error.to_string(), "eof");
+    assert!(error.downcast_ref::<std::io::Error>().is_some());
+}
+
+#[test]
+fn test_arc_generic() {
+    let error: ArcLookupError<String> = ArcLookupError::not_found("foo");
+    let cloned = error.clone();
+    assert_eq!(cloned.to_string(), "key `foo` not found");
+
+    // `Clone` is not required for the generic parameter.
+    #[derive(Debug)]
+    struct Key;
+    impl Display for Key {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("key")
+        }
+    }
+    let error = ArcLookupError::not_found(Key);
+    let cloned = error.clone();
+    assert!(matches!(cloned.inner(), LookupErrorInner::NotFound(Key)));
+    drop(cloned);
+    assert!(error.try_into_inner().is_ok());
+}
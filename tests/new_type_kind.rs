@@ -0,0 +1,77 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+use thiserror_ext::{Arc, Box};
+
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = MyError, kind))]
+enum MyErrorInner {
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("timeout after {seconds}s")]
+    Timeout { seconds: u64 },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Error, Debug, Arc)]
+#[thiserror_ext(newtype(name = SharedError, kind = SharedErrorCategory))]
+enum SharedErrorInner {
+    #[error("internal")]
+    Internal,
+}
+
+#[test]
+fn test_kind() {
+    let errors: Vec<MyError> = vec![
+        MyErrorInner::NotFound("foo".to_owned()).into(),
+        MyErrorInner::Timeout { seconds: 1 }.into(),
+        MyErrorInner::NotFound("bar".to_owned()).into(),
+        std::io::Error::other("oops").into(),
+    ];
+
+    let mut counts = HashMap::new();
+    for error in &errors {
+        *counts.entry(error.kind()).or_insert(0) += 1;
+    }
+
+    assert_eq!(counts[&MyErrorKind::NotFound], 2);
+    assert_eq!(counts[&MyErrorKind::Timeout], 1);
+    assert_eq!(counts[&MyErrorKind::Io], 1);
+}
+
+#[test]
+fn test_kind_custom_name() {
+    let error: SharedError = SharedErrorInner::Internal.into();
+    assert_eq!(error.kind(), SharedErrorCategory::Internal);
+    assert_eq!(format!("{:?}", error.clone().kind()), "Internal");
+}
+
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = AliasError, kind_alias))]
+enum AliasErrorKind {
+    #[error("bad id: {0}")]
+    BadId(String),
+}
+
+#[derive(Error, Debug, Arc)]
+#[thiserror_ext(newtype(name = SharedAliasError, kind_alias))]
+enum SharedAliasErrorKind {
+    #[error("internal")]
+    Internal,
+}
+
+#[test]
+fn test_kind_alias() {
+    let error: AliasError = AliasErrorKind::BadId("42".to_owned()).into();
+    let AliasErrorKind::BadId(id) = error.kind();
+    assert_eq!(id, "42");
+
+    let AliasErrorKind::BadId(id) = error.into_kind();
+    assert_eq!(id, "42");
+
+    let error: SharedAliasError = SharedAliasErrorKind::Internal.into();
+    assert!(matches!(error.kind(), SharedAliasErrorKind::Internal));
+}
@@ -0,0 +1,79 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+#[cfg(feature = "backtrace")]
+use sealed_test::prelude::*;
+use thiserror::Error;
+use thiserror_ext::{Arc, Box};
+
+#[derive(Error, Debug, Default)]
+#[error("inner")]
+struct Inner;
+
+#[derive(Error, Debug, Default, Box)]
+#[thiserror_ext(newtype(name = BoxOuter, report_debug))]
+#[error("outer")]
+struct Outer {
+    #[source]
+    inner: Inner,
+}
+
+#[derive(Error, Debug, Default, Clone, PartialEq, Arc)]
+#[thiserror_ext(newtype(name = ArcOuter, report_debug, eq))]
+#[error("shared outer")]
+struct SharedOuter {
+    #[source]
+    inner: SharedInner,
+}
+
+#[derive(Error, Debug, Default, Clone, PartialEq)]
+#[error("shared inner")]
+struct SharedInner;
+
+#[test]
+fn test_report_debug() {
+    let boxed = BoxOuter::from(Outer::default());
+
+    expect_test::expect!["outer: inner"].assert_eq(&format!("{:?}", boxed));
+
+    expect_test::expect![[r#"
+    outer
+
+    Caused by:
+      inner
+"#]]
+    .assert_eq(&format!("{:#?}", boxed));
+
+    // The original error type is not affected.
+    expect_test::expect!["Outer { inner: Inner }"].assert_eq(&format!("{:?}", boxed.inner()));
+}
+
+#[test]
+fn test_report_debug_with_other_options() {
+    let shared = ArcOuter::from(SharedOuter::default());
+    assert_eq!(shared, shared.clone());
+
+    expect_test::expect!["shared outer: shared inner"].assert_eq(&format!("{:?}", shared));
+}
+
+#[cfg(feature = "backtrace")]
+#[sealed_test(env = [("RUST_BACKTRACE", "0"), ("THISERROR_EXT_TEST_SHOW_USELESS_BACKTRACE", "1")])]
+fn test_report_debug_with_backtrace() {
+    #[derive(Error, Debug, Default, Box)]
+    #[thiserror_ext(newtype(name = BoxBacktraceOuter, report_debug, backtrace))]
+    #[error("outer")]
+    struct BacktraceOuter {
+        #[source]
+        inner: Inner,
+    }
+
+    let boxed = BoxBacktraceOuter::from(BacktraceOuter::default());
+    let debug = format!("{:?}", boxed);
+    let pretty = format!("{:#?}", boxed);
+
+    // The backtrace captured by the new type is shown.
+    assert!(debug.starts_with("outer: inner\n\nBacktrace:\n"), "{debug}");
+    assert!(
+        pretty.starts_with("outer\n\nCaused by:\n  inner\n\nBacktrace:\n"),
+        "{pretty}"
+    );
+}
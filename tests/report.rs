@@ -157,3 +157,134 @@ fn test_report_debug_alternate_disabled_backtrace() {
     "#]];
     expect.assert_eq(&format!("{:#?}", outer(true).unwrap_err().as_report()));
 }
+
+#[test]
+fn test_report_provide_backtrace() {
+    use std::backtrace::Backtrace;
+
+    let error = outer(true).unwrap_err();
+    let report = error.as_report();
+    assert!(std::error::request_ref::<Backtrace>(&report).is_some());
+
+    let error = outer(false).unwrap_err();
+    let report = error.as_report();
+    assert!(std::error::request_ref::<Backtrace>(&report).is_none());
+}
+
+// Provides its own backtrace only. A `thiserror`-derived error would provide
+// the backtrace of the source first if there's one.
+#[derive(Debug)]
+struct Top {
+    source: Outer,
+    backtrace: std::backtrace::Backtrace,
+}
+
+impl std::fmt::Display for Top {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "top error")
+    }
+}
+
+impl std::error::Error for Top {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        request.provide_ref(&self.backtrace);
+    }
+}
+
+fn top() -> Top {
+    Top {
+        source: outer(true).unwrap_err(),
+        backtrace: std::backtrace::Backtrace::capture(),
+    }
+}
+
+// Only the backtrace provided by the top error is shown by default.
+#[sealed_test(env = [("RUST_BACKTRACE", "0"), ("THISERROR_EXT_TEST_SHOW_USELESS_BACKTRACE", "1")])]
+fn test_report_debug_top_backtrace() {
+    let expect = expect![[r#"
+        top error: outer error: middle error: inner error
+
+        Backtrace:
+        disabled backtrace
+    "#]];
+    expect.assert_eq(&format!("{:?}", top().as_report()));
+}
+
+// The backtrace propagated from `Middle` is only shown once, labeled with its message.
+#[sealed_test(env = [("RUST_BACKTRACE", "0"), ("THISERROR_EXT_TEST_SHOW_USELESS_BACKTRACE", "1")])]
+fn test_report_debug_all_backtraces() {
+    let expect = expect![[r#"
+        top error: outer error: middle error: inner error
+
+        Backtrace of `top error`:
+        disabled backtrace
+
+        Backtrace of `middle error`:
+        disabled backtrace
+    "#]];
+    expect.assert_eq(&format!("{:?}", top().as_report().all_backtraces()));
+
+    let expect = expect![[r#"
+        outer error
+
+        Caused by these errors (recent errors listed first):
+          1: middle error
+          2: inner error
+
+        Backtrace of `middle error`:
+        disabled backtrace
+    "#]];
+    expect.assert_eq(&format!(
+        "{:#?}",
+        outer(true).unwrap_err().as_report().all_backtraces()
+    ));
+}
+
+#[sealed_test(env = [("RUST_BACKTRACE", "0")])]
+fn test_report_debug_all_backtraces_disabled() {
+    let expect = expect!["top error: outer error: middle error: inner error"];
+    expect.assert_eq(&format!("{:?}", top().as_report().all_backtraces()));
+}
+
+// The backtrace of a circular error is labeled with the last message before
+// the chain is cut off, whether cleaned or not.
+#[sealed_test(env = [("RUST_BACKTRACE", "0"), ("THISERROR_EXT_TEST_SHOW_USELESS_BACKTRACE", "1")])]
+fn test_report_debug_all_backtraces_circular() {
+    #[derive(Debug)]
+    struct SelfSource(std::backtrace::Backtrace);
+
+    impl std::fmt::Display for SelfSource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "self source")
+        }
+    }
+
+    impl std::error::Error for SelfSource {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(self)
+        }
+
+        fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+            request.provide_ref(&self.0);
+        }
+    }
+
+    let error = SelfSource(std::backtrace::Backtrace::capture());
+
+    let expect = expect![[r#"
+        self source (cycle detected or chain too deep)
+
+        Backtrace of `self source (cycle detected or chain too deep)`:
+        disabled backtrace
+    "#]];
+    expect.assert_eq(&format!("{:?}", error.as_report().all_backtraces()));
+
+    let report = error.as_report().with_cleaning(false).all_backtraces();
+    assert!(format!("{:?}", report).ends_with(
+        "\n\nBacktrace of `self source (cycle detected or chain too deep)`:\ndisabled backtrace\n"
+    ));
+}
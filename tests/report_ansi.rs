@@ -0,0 +1,35 @@
+#![cfg(feature = "ansi")]
+
+mod common;
+
+use common::outer;
+use expect_test::expect;
+use sealed_test::prelude::*;
+use thiserror_ext::AsReport;
+
+#[sealed_test(env = [("NO_COLOR", "")])]
+fn test_ansi() {
+    let expect = expect!["\u{1b}[1mouter error\u{1b}[0m: middle error: inner error"];
+    expect.assert_eq(&outer().as_report().with_ansi(true).to_string());
+
+    assert_eq!(
+        format!("{:#}", outer().as_report().with_ansi(true)),
+        "\u{1b}[1mouter error\u{1b}[0m\n\n\
+         \u{1b}[2mCaused by these errors (recent errors listed first):\u{1b}[0m\n\
+         \u{1b}[36m  1\u{1b}[0m: middle error\n\
+         \u{1b}[36m  2\u{1b}[0m: inner error\n"
+    );
+}
+
+#[sealed_test(env = [("NO_COLOR", "")])]
+fn test_ansi_disabled() {
+    let expect = expect!["outer error: middle error: inner error"];
+    expect.assert_eq(&outer().as_report().with_ansi(false).to_string());
+    expect.assert_eq(&outer().as_report().to_string());
+}
+
+#[sealed_test(env = [("NO_COLOR", "1")])]
+fn test_ansi_no_color() {
+    let expect = expect!["outer error: middle error: inner error"];
+    expect.assert_eq(&outer().as_report().with_ansi(true).to_string());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use anyhow::Context;
+use common::{middle, Inner};
+use thiserror_ext::AsReport;
+
+fn anyhow_error() -> anyhow::Error {
+    Err::<(), _>(middle()).context("outer context").unwrap_err()
+}
+
+#[test]
+fn test_anyhow_report() {
+    let error = anyhow_error();
+
+    // The chain of `anyhow`, including the contexts, is walked as is, and the
+    // messages are cleaned in the same way.
+    assert_eq!(
+        error.to_report_string(),
+        "outer context: middle error: inner error"
+    );
+    assert_eq!(
+        error.to_report_string_pretty(),
+        "outer context\n\nCaused by these errors (recent errors listed first):\n  1: middle error\n  2: inner error\n"
+    );
+    assert_eq!(error.source_count(), 2);
+    assert_eq!(error.root_cause().to_string(), "inner error");
+    assert!(error.find_source::<Inner>().is_some());
+}
+
+#[test]
+fn test_anyhow_report_no_double_wrapping() {
+    let error = anyhow_error();
+
+    // The report is built on the error `anyhow` wraps, instead of a new
+    // wrapper around `anyhow::Error` itself.
+    let report = error.as_report();
+    let inner: &(dyn std::error::Error + Send + Sync) = error.as_ref();
+    assert!(std::ptr::addr_eq(report.0, inner));
+    assert_eq!(report.chain().count(), error.chain().count());
+}
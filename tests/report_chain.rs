@@ -0,0 +1,416 @@
+mod common;
+
+use common::{outer, Inner, Middle, MiddleTransparent, Outer};
+use thiserror::Error;
+use thiserror_ext::{AsReport, CleanedErrorText};
+
+#[test]
+fn test_iter_cleaned() {
+    let error = outer();
+    let items: Vec<_> = error
+        .as_report()
+        .iter_cleaned()
+        .map(|(error, msg, cleaned)| (error.to_string(), msg, cleaned))
+        .collect();
+
+    assert_eq!(
+        items,
+        [
+            ("outer error".to_owned(), "outer error".to_owned(), false),
+            ("middle error: inner error".to_owned(), "".to_owned(), true),
+            (
+                "middle error: inner error".to_owned(),
+                "middle error".to_owned(),
+                true
+            ),
+            ("inner error".to_owned(), "inner error".to_owned(), false),
+        ]
+    );
+}
+
+#[test]
+fn test_cleaned_error_text() {
+    let error = outer();
+    let messages: Vec<_> = CleanedErrorText::new(&error)
+        .map(|(_error, msg, _cleaned)| msg)
+        .collect();
+
+    assert_eq!(messages, ["outer error", "", "middle error", "inner error"]);
+}
+
+#[derive(Error, Debug)]
+enum Duplicated {
+    #[error("{source}: while doing X")]
+    Prefix { source: Inner },
+    #[error("while doing X: {source}")]
+    Suffix { source: Inner },
+    #[error("{source}: while doing X: {source}")]
+    Both { source: Inner },
+    #[error("failed with {source}: while doing X")]
+    Middle { source: Inner },
+}
+
+#[test]
+fn test_cleaned_error_text_duplicated() {
+    let cleaned = |error: Duplicated| {
+        let (_, msg, cleaned) = CleanedErrorText::new(&error).next().unwrap();
+        (msg, cleaned)
+    };
+
+    assert_eq!(
+        cleaned(Duplicated::Prefix { source: Inner }),
+        ("while doing X".to_owned(), true)
+    );
+    assert_eq!(
+        cleaned(Duplicated::Suffix { source: Inner }),
+        ("while doing X".to_owned(), true)
+    );
+    assert_eq!(
+        cleaned(Duplicated::Both { source: Inner }),
+        ("while doing X".to_owned(), true)
+    );
+    assert_eq!(
+        cleaned(Duplicated::Middle { source: Inner }),
+        ("failed with inner error: while doing X".to_owned(), false)
+    );
+
+    assert_eq!(
+        Duplicated::Prefix { source: Inner }.to_report_string(),
+        "while doing X: inner error"
+    );
+}
+
+#[derive(Error, Debug)]
+#[error("wrapper error")]
+struct Wrapper {
+    source: Outer,
+}
+
+#[test]
+fn test_find_source() {
+    let error = outer();
+
+    assert!(error.find_source::<MiddleTransparent>().is_some());
+    assert!(error.find_source::<Inner>().is_some());
+    // The error itself is excluded.
+    assert!(error.find_source::<Outer>().is_none());
+    assert!(error.find_source::<std::io::Error>().is_none());
+
+    let middle = error.find_source::<Middle>().unwrap();
+    assert_eq!(middle.to_string(), "middle error: inner error");
+
+    // Also works on trait objects.
+    let error: &(dyn std::error::Error + Send + Sync) = &error;
+    assert!(error.find_source::<Inner>().is_some());
+}
+
+#[test]
+fn test_find_source_shallowest() {
+    #[derive(Error, Debug)]
+    #[error("nested {depth}")]
+    struct Nested {
+        depth: usize,
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    }
+
+    let error = Wrapper { source: outer() };
+    assert!(error.find_source::<Outer>().is_some());
+
+    let error = Nested {
+        depth: 0,
+        source: Some(Box::new(Nested {
+            depth: 1,
+            source: Some(Box::new(Nested {
+                depth: 2,
+                source: None,
+            })),
+        })),
+    };
+    assert_eq!(error.find_source::<Nested>().unwrap().depth, 1);
+}
+
+#[test]
+fn test_find_source_circular() {
+    #[derive(Debug)]
+    struct Circular;
+
+    impl std::fmt::Display for Circular {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "circular")
+        }
+    }
+
+    impl std::error::Error for Circular {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(self)
+        }
+    }
+
+    assert!(Circular.find_source::<Inner>().is_none());
+    assert!(Circular.find_source::<Circular>().is_some());
+}
+
+#[test]
+fn test_report_as_error() {
+    let error = outer();
+    let report = error.as_report();
+
+    let source = std::error::Error::source(&report).unwrap();
+    assert_eq!(source.to_string(), "middle error: inner error");
+    assert!(source.downcast_ref::<MiddleTransparent>().is_some());
+
+    let boxed: Box<dyn std::error::Error + '_> = Box::new(report);
+    assert_eq!(boxed.to_string(), "outer error: middle error: inner error");
+}
+
+#[test]
+fn test_source_count_and_all_messages() {
+    assert_eq!(Inner.source_count(), 0);
+    assert_eq!(Inner.all_messages(), ["inner error"]);
+
+    let middle = Middle { source: Inner };
+    assert_eq!(middle.source_count(), 1);
+    assert_eq!(middle.all_messages(), ["middle error", "inner error"]);
+
+    // The transparent one is not counted.
+    let error = outer();
+    assert_eq!(error.source_count(), 2);
+    assert_eq!(
+        error.all_messages(),
+        ["outer error", "middle error", "inner error"]
+    );
+}
+
+#[derive(Debug)]
+struct Cycle {
+    message: &'static str,
+    next: std::cell::OnceCell<&'static Cycle>,
+}
+
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Cycle {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.next.get().map(|e| *e as _)
+    }
+}
+
+#[test]
+fn test_report_circular() {
+    let a: &'static Cycle = Box::leak(Box::new(Cycle {
+        message: "a",
+        next: Default::default(),
+    }));
+    let b: &'static Cycle = Box::leak(Box::new(Cycle {
+        message: "b",
+        next: Default::default(),
+    }));
+    a.next.set(b).unwrap();
+    b.next.set(a).unwrap();
+
+    let messages = a.all_messages();
+    assert_eq!(messages.len(), 65);
+    assert_eq!(messages[..4], ["a", "b", "a", "b"]);
+    assert_eq!(
+        messages.last().unwrap(),
+        "a (cycle detected or chain too deep)"
+    );
+    assert!(a
+        .to_report_string()
+        .ends_with(": b: a (cycle detected or chain too deep)"));
+
+    // Also marked if not cleaned.
+    assert!(a
+        .as_report()
+        .with_cleaning(false)
+        .to_string()
+        .ends_with(": b: a (cycle detected or chain too deep)"));
+}
+
+#[test]
+fn test_report_self_source() {
+    #[derive(Debug)]
+    struct SelfSource;
+
+    impl std::fmt::Display for SelfSource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "self source")
+        }
+    }
+
+    impl std::error::Error for SelfSource {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(self)
+        }
+    }
+
+    assert_eq!(
+        SelfSource.to_report_string(),
+        "self source (cycle detected or chain too deep)"
+    );
+    assert_eq!(SelfSource.source_count(), 0);
+}
+
+#[test]
+fn test_root_cause() {
+    assert_eq!(Inner.root_cause().to_string(), "inner error");
+    assert_eq!(outer().root_cause().to_string(), "inner error");
+    assert_eq!(outer().as_report().root_cause().to_string(), "inner error");
+
+    let error = outer();
+    let root_cause = error.root_cause() as *const dyn std::error::Error as *const ();
+    let inner = error.find_source::<Inner>().unwrap() as *const Inner as *const ();
+    assert_eq!(root_cause, inner);
+}
+
+#[test]
+fn test_eq_chain() {
+    #[derive(Error, Debug)]
+    #[error("{message}")]
+    struct Chain {
+        message: &'static str,
+        source: Option<Box<Chain>>,
+    }
+
+    fn chain(messages: &[&'static str]) -> Chain {
+        let (message, rest) = messages.split_first().unwrap();
+        Chain {
+            message,
+            source: (!rest.is_empty()).then(|| Box::new(chain(rest))),
+        }
+    }
+
+    // The transparent middle error is cleaned to empty and skipped.
+    let expected = chain(&["outer error", "middle error", "inner error"]);
+    assert!(outer().as_report().eq_chain(&expected.as_report()));
+    assert!(expected.as_report().eq_chain(&outer().as_report()));
+
+    let different = chain(&["outer error", "inner error"]);
+    assert!(!outer().as_report().eq_chain(&different.as_report()));
+
+    // Respects the cleaning option of each report.
+    assert!(!outer()
+        .as_report()
+        .with_cleaning(false)
+        .eq_chain(&expected.as_report().formatted()));
+}
+
+#[test]
+fn test_as_report_on_pointers() {
+    fn report_string<E: AsReport + ?Sized>(error: &E) -> String {
+        error.to_report_string()
+    }
+    let expected = "outer error: middle error: inner error";
+
+    let error = outer();
+    assert_eq!((&&error).as_report().to_string(), expected);
+    assert_eq!(report_string(&&error), expected);
+
+    let error: Box<Outer> = Box::new(outer());
+    assert_eq!(error.to_report_string(), expected);
+    assert_eq!(report_string(&error), expected);
+
+    let error: Box<dyn std::error::Error> = Box::new(outer());
+    assert_eq!(error.as_report().to_string(), expected);
+    assert_eq!(report_string(&*error), expected);
+
+    let error: Box<dyn std::error::Error + Send + Sync> = Box::new(outer());
+    assert_eq!(error.to_report_string(), expected);
+    assert_eq!(report_string(&*error), expected);
+
+    let error: std::sync::Arc<dyn std::error::Error + Send + Sync> = std::sync::Arc::new(outer());
+    assert_eq!(error.to_report_string(), expected);
+    assert_eq!(report_string(&error), expected);
+}
+
+#[test]
+fn test_chain() {
+    let error = outer();
+    let chain = error.as_report().chain();
+    assert_eq!(chain.len(), 4);
+
+    let messages: Vec<_> = chain.map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "outer error",
+            "middle error: inner error",
+            "middle error: inner error",
+            "inner error",
+        ]
+    );
+
+    let mut chain = error.as_report().chain();
+    assert_eq!(chain.next().unwrap().to_string(), "outer error");
+    assert_eq!(chain.len(), 3);
+    assert_eq!(chain.nth(2).unwrap().to_string(), "inner error");
+    assert_eq!(chain.len(), 0);
+    assert!(chain.next().is_none());
+
+    assert_eq!(Inner.as_report().chain().count(), 1);
+}
+
+#[test]
+fn test_chain_circular() {
+    #[derive(Debug)]
+    struct Circular;
+
+    impl std::fmt::Display for Circular {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "circular")
+        }
+    }
+
+    impl std::error::Error for Circular {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(self)
+        }
+    }
+
+    let error = Circular;
+    let chain = error.as_report().chain();
+    let len = chain.len();
+    assert_eq!(chain.count(), len);
+    assert!(len > 1);
+}
+
+#[test]
+fn test_chain_len_counted_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SOURCE_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct Link(&'static str, Option<&'static Link>);
+
+    impl std::fmt::Display for Link {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl std::error::Error for Link {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            SOURCE_CALLS.fetch_add(1, Ordering::Relaxed);
+            self.1.map(|e| e as _)
+        }
+    }
+
+    static INNER: Link = Link("inner", None);
+    static MIDDLE: Link = Link("middle", Some(&INNER));
+    static OUTER: Link = Link("outer", Some(&MIDDLE));
+
+    let mut chain = OUTER.as_report().chain();
+    assert_eq!(SOURCE_CALLS.load(Ordering::Relaxed), 3);
+
+    // Querying the length does not walk the chain again.
+    while chain.len() > 0 {
+        assert_eq!(chain.size_hint(), (chain.len(), Some(chain.len())));
+        chain.next();
+    }
+    assert_eq!(SOURCE_CALLS.load(Ordering::Relaxed), 6);
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "color")]
+
+mod common;
+
+use common::outer;
+use expect_test::expect;
+use sealed_test::prelude::*;
+use thiserror_ext::{AsReport, ColoredReport};
+
+#[sealed_test(env = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "")])]
+fn test_color() {
+    assert_eq!(
+        outer().as_colored_report().to_string(),
+        "\u{1b}[31mouter error\u{1b}[0m: middle error: inner error"
+    );
+
+    assert_eq!(
+        format!("{:#}", outer().as_colored_report()),
+        "\u{1b}[31mouter error\u{1b}[0m\n\n\
+         \u{1b}[33mCaused by these errors (recent errors listed first):\u{1b}[0m\n\
+         \u{1b}[90m  1\u{1b}[0m: middle error\n\
+         \u{1b}[90m  2\u{1b}[0m: inner error\n"
+    );
+}
+
+#[sealed_test(env = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "")])]
+fn test_color_from_report() {
+    let error = outer();
+    let report = ColoredReport::from(error.as_report().with_separator(" <- "));
+    assert_eq!(
+        report.to_string(),
+        "\u{1b}[31mouter error\u{1b}[0m <- middle error <- inner error"
+    );
+}
+
+#[sealed_test(env = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")])]
+fn test_color_no_color() {
+    let expect = expect!["outer error: middle error: inner error"];
+    expect.assert_eq(&outer().as_colored_report().to_string());
+}
@@ -0,0 +1,52 @@
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use thiserror::Error;
+use thiserror_ext::{Box, ReportDisplay};
+
+#[derive(Error, Debug)]
+#[error("inner")]
+struct Inner;
+
+#[derive(Error, Debug)]
+#[error("outer")]
+struct Outer {
+    #[source]
+    inner: Inner,
+}
+
+#[derive(Debug, ReportDisplay)]
+struct Displayed(Outer);
+
+#[derive(Error, Debug, ReportDisplay, Box)]
+#[thiserror_ext(newtype(name = BoxDisplayed))]
+struct DisplayedError {
+    error: Outer,
+}
+
+fn outer() -> Outer {
+    Outer { inner: Inner }
+}
+
+#[test]
+fn test_report_display() {
+    let displayed = Displayed(outer());
+
+    expect_test::expect!["outer: inner"].assert_eq(&displayed.to_string());
+
+    expect_test::expect![[r#"
+    outer
+
+    Caused by:
+      inner
+"#]]
+    .assert_eq(&format!("{:#}", displayed));
+}
+
+#[test]
+fn test_report_display_new_type() {
+    let error = DisplayedError { error: outer() };
+    expect_test::expect!["outer: inner"].assert_eq(&error.to_string());
+
+    let boxed = BoxDisplayed::from(error);
+    expect_test::expect!["outer: inner"].assert_eq(&boxed.to_string());
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{middle, Inner};
+use eyre::WrapErr;
+use thiserror_ext::AsReport;
+
+fn eyre_report() -> eyre::Report {
+    Err::<(), _>(middle())
+        .wrap_err("outer context")
+        .unwrap_err()
+}
+
+#[test]
+fn test_eyre_report() {
+    let error = eyre_report();
+
+    assert_eq!(
+        error.to_report_string(),
+        "outer context: middle error: inner error"
+    );
+    assert_eq!(
+        error.to_report_string_pretty(),
+        "outer context\n\nCaused by these errors (recent errors listed first):\n  1: middle error\n  2: inner error\n"
+    );
+    assert_eq!(error.root_cause().to_string(), "inner error");
+    assert!(error.find_source::<Inner>().is_some());
+}
+
+#[test]
+fn test_eyre_report_no_double_wrapping() {
+    let error = eyre_report();
+
+    let report = error.as_report();
+    let inner: &(dyn std::error::Error + Send + Sync) = error.as_ref();
+    assert!(std::ptr::addr_eq(report.0, inner));
+    assert_eq!(report.chain().count(), error.chain().count());
+}
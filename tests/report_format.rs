@@ -0,0 +1,325 @@
+mod common;
+
+use common::{middle, outer, Inner};
+use expect_test::expect;
+use thiserror::Error;
+use thiserror_ext::{AsReport, Report, ReportHeaders};
+
+#[test]
+fn test_separator() {
+    let expect = expect!["outer error -> middle error -> inner error"];
+    expect.assert_eq(&outer().as_report().with_separator(" -> ").to_string());
+
+    let expect = expect!["middle error | inner error"];
+    expect.assert_eq(&middle().as_report().with_separator(" | ").to_string());
+
+    let expect = expect!["inner error"];
+    expect.assert_eq(&Inner.as_report().with_separator(" | ").to_string());
+}
+
+#[test]
+fn test_as_report_with_separator() {
+    let expect = expect!["outer error -> middle error -> inner error"];
+    expect.assert_eq(&outer().as_report_with_separator(" -> ").to_string());
+
+    // The options can be further chained.
+    let expect = expect!["outer error -> middle error -> ... (1 more)"];
+    expect.assert_eq(
+        &outer()
+            .as_report_with_separator(" -> ")
+            .with_max_depth(1)
+            .to_string(),
+    );
+}
+
+#[test]
+fn test_report_tuple_constructor() {
+    // `Report` can still be constructed directly with the error.
+    let error = outer();
+    let report = Report(&error);
+    assert_eq!(report.to_string(), "outer error: middle error: inner error");
+    assert_eq!(
+        report.with_separator(" -> ").to_string(),
+        "outer error -> middle error -> inner error"
+    );
+}
+
+#[test]
+fn test_separator_pretty_unaffected() {
+    let expect = expect![[r#"
+        outer error
+
+        Caused by these errors (recent errors listed first):
+          1: middle error
+          2: inner error
+    "#]];
+    expect.assert_eq(&format!("{:#}", outer().as_report().with_separator(" -> ")));
+}
+
+#[test]
+fn test_headers() {
+    let headers = ReportHeaders {
+        single: "Reason:".into(),
+        multiple: "Reasons:".into(),
+    };
+
+    let expect = expect![[r#"
+        outer error
+
+        Reasons:
+          1: middle error
+          2: inner error
+    "#]];
+    expect.assert_eq(&format!(
+        "{:#}",
+        outer().as_report().with_headers(headers.clone())
+    ));
+
+    let expect = expect![[r#"
+        middle error
+
+        Reason:
+          inner error
+    "#]];
+    expect.assert_eq(&format!("{:#}", middle().as_report().with_headers(headers)));
+}
+
+#[test]
+fn test_max_depth() {
+    let expect = expect!["outer error: middle error: inner error"];
+    expect.assert_eq(&outer().as_report().with_max_depth(2).to_string());
+    expect.assert_eq(&outer().as_report().with_max_depth(3).to_string());
+
+    let expect = expect!["outer error: middle error: ... (1 more)"];
+    expect.assert_eq(&outer().as_report().with_max_depth(1).to_string());
+
+    let expect = expect!["outer error: ... (2 more)"];
+    expect.assert_eq(&outer().as_report().with_max_depth(0).to_string());
+
+    let expect = expect!["inner error"];
+    expect.assert_eq(&Inner.as_report().with_max_depth(0).to_string());
+}
+
+#[test]
+fn test_max_depth_pretty() {
+    let expect = expect![[r#"
+        outer error
+
+        Caused by these errors (recent errors listed first):
+          1: middle error
+          2: inner error
+    "#]];
+    expect.assert_eq(&format!("{:#}", outer().as_report().with_max_depth(2)));
+
+    let expect = expect![[r#"
+        outer error
+
+        Caused by these errors (recent errors listed first):
+          1: middle error
+          ... (1 more)
+    "#]];
+    expect.assert_eq(&format!("{:#}", outer().as_report().with_max_depth(1)));
+
+    let expect = expect![[r#"
+        outer error
+
+        Caused by these errors (recent errors listed first):
+          ... (2 more)
+    "#]];
+    expect.assert_eq(&format!("{:#}", outer().as_report().with_max_depth(0)));
+
+    let expect = expect![[r#"
+        middle error
+
+        Caused by:
+          ... (1 more)
+    "#]];
+    expect.assert_eq(&format!("{:#}", middle().as_report().with_max_depth(0)));
+}
+
+#[test]
+fn test_max_depth_debug() {
+    let expect = expect!["outer error: middle error: ... (1 more)"];
+    expect.assert_eq(&format!("{:?}", outer().as_report().with_max_depth(1)));
+
+    let expect = expect![[r#"
+        outer error
+
+        Caused by these errors (recent errors listed first):
+          1: middle error
+          ... (1 more)
+    "#]];
+    expect.assert_eq(&format!("{:#?}", outer().as_report().with_max_depth(1)));
+}
+
+#[derive(Error, Debug)]
+#[error("读取文件失败")]
+struct Unicode {
+    source: Inner,
+}
+
+#[test]
+fn test_truncated() {
+    let full = outer().to_report_string();
+    assert_eq!(full.len(), 38);
+
+    // Fits exactly.
+    assert_eq!(outer().to_report_string_truncated(38), full);
+    assert_eq!(outer().to_report_string_truncated(100), full);
+
+    let expect = expect!["outer error: middle error: inner..."];
+    expect.assert_eq(&outer().to_report_string_truncated(37));
+    let expect = expect!["outer error: middle..."];
+    expect.assert_eq(&outer().to_report_string_truncated(25));
+    let expect = expect!["outer..."];
+    expect.assert_eq(&outer().to_report_string_truncated(10));
+
+    // No whitespace to cut at.
+    let expect = expect!["ou..."];
+    expect.assert_eq(&outer().to_report_string_truncated(5));
+    let expect = expect![".."];
+    expect.assert_eq(&outer().to_report_string_truncated(2));
+}
+
+#[test]
+fn test_truncated_unicode() {
+    let error = Unicode { source: Inner };
+    let full = error.to_report_string();
+    assert_eq!(full.len(), 31);
+    assert_eq!(error.to_report_string_truncated(31), full);
+
+    // Each character takes 3 bytes, never split.
+    for max_len in 0..31 {
+        let truncated = error.to_report_string_truncated(max_len);
+        assert!(truncated.len() <= max_len, "{max_len}: {truncated}");
+    }
+
+    let expect = expect!["读取文件失败:..."];
+    expect.assert_eq(&error.to_report_string_truncated(24));
+    let expect = expect!["读取..."];
+    expect.assert_eq(&error.to_report_string_truncated(11));
+}
+
+#[test]
+fn test_without_cleaning() {
+    let expect =
+        expect!["outer error: middle error: inner error: middle error: inner error: inner error"];
+    expect.assert_eq(&outer().as_report().with_cleaning(false).to_string());
+
+    let expect = expect![[r#"
+        outer error
+
+        Caused by these errors (recent errors listed first):
+          1: middle error: inner error
+          2: middle error: inner error
+          3: inner error
+    "#]];
+    expect.assert_eq(&format!("{:#}", outer().as_report().with_cleaning(false)));
+
+    // Cleaning is enabled by default.
+    let expect = expect!["outer error: middle error: inner error"];
+    expect.assert_eq(&outer().as_report().with_cleaning(true).to_string());
+}
+
+#[test]
+fn test_numbered() {
+    let expect = expect![[r#"
+        1: outer error
+        2: middle error
+        3: inner error"#]];
+    expect.assert_eq(&outer().as_report().numbered().to_string());
+    // The alternate flag makes no difference.
+    expect.assert_eq(&format!("{:#}", outer().as_report().numbered()));
+
+    let expect = expect!["inner error"];
+    expect.assert_eq(&Inner.as_report().numbered().to_string());
+
+    let expect = expect![[r#"
+        1: outer error
+        2: middle error
+        ... (1 more)"#]];
+    expect.assert_eq(&outer().as_report().numbered().with_max_depth(1).to_string());
+}
+
+#[derive(Error, Debug)]
+#[error("failed to execute query:\n{sql}")]
+struct QueryError {
+    sql: &'static str,
+    source: Option<PlanError>,
+}
+
+#[derive(Error, Debug)]
+#[error("failed to plan:\n\nunknown column `x`")]
+struct PlanError {
+    source: Inner,
+}
+
+#[test]
+fn test_multi_line_pretty() {
+    let sql = "SELECT x\nFROM t";
+
+    let error = QueryError {
+        sql,
+        source: Some(PlanError { source: Inner }),
+    };
+    let expect = expect![[r#"
+        failed to execute query:
+        SELECT x
+        FROM t
+
+        Caused by these errors (recent errors listed first):
+          1: failed to plan:
+
+             unknown column `x`
+          2: inner error
+    "#]];
+    expect.assert_eq(&format!("{:#}", error.as_report()));
+
+    let error = PlanError { source: Inner };
+    let expect = expect![[r#"
+        failed to plan:
+
+        unknown column `x`
+
+        Caused by:
+          inner error
+    "#]];
+    expect.assert_eq(&format!("{:#}", error.as_report()));
+
+    #[derive(Error, Debug)]
+    #[error("request failed")]
+    struct RequestError {
+        source: QueryError,
+    }
+
+    let error = RequestError {
+        source: QueryError { sql, source: None },
+    };
+    let expect = expect![[r#"
+        request failed
+
+        Caused by:
+          failed to execute query:
+          SELECT x
+          FROM t
+    "#]];
+    expect.assert_eq(&format!("{:#}", error.as_report()));
+
+    let error = QueryError { sql, source: None };
+    let expect = expect![[r#"
+        1: failed to plan:
+
+           unknown column `x`
+        2: inner error"#]];
+    expect.assert_eq(
+        &PlanError { source: Inner }
+            .as_report()
+            .numbered()
+            .to_string(),
+    );
+    let expect = expect![[r#"
+        failed to execute query:
+        SELECT x
+        FROM t"#]];
+    expect.assert_eq(&error.as_report().numbered().to_string());
+}
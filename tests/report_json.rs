@@ -0,0 +1,102 @@
+#![cfg(feature = "serde")]
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+mod common;
+
+use common::{outer, Inner};
+use expect_test::expect;
+#[cfg(feature = "backtrace")]
+use sealed_test::prelude::*;
+use thiserror_ext::AsReport;
+
+#[test]
+fn test_to_json_string() {
+    let expect = expect![[r#"{"message":"outer error","causes":["middle error","inner error"]}"#]];
+    expect.assert_eq(&outer().to_json_string());
+
+    let expect = expect![[r#"{"message":"inner error","causes":[]}"#]];
+    expect.assert_eq(&Inner.to_json_string());
+}
+
+#[test]
+fn test_to_json_value() {
+    let expect = expect![[
+        r#"[{"message":"outer error"},{"message":"middle error"},{"message":"inner error"}]"#
+    ]];
+    expect.assert_eq(&outer().as_report().to_json_value().to_string());
+
+    let expect = expect![[r#"[{"message":"inner error"}]"#]];
+    expect.assert_eq(&Inner.as_report().to_json_value().to_string());
+}
+
+/// The serialized report and `to_json_value` intentionally have different
+/// schemas. Pin both for the same report, so that they neither converge nor
+/// drift by accident.
+#[test]
+fn test_json_schemas_differ() {
+    let error = outer();
+    let report = error.as_report();
+
+    let serialized = serde_json::to_value(&report).unwrap();
+    let value = report.to_json_value();
+    assert_ne!(serialized, value);
+
+    assert_eq!(
+        serialized,
+        serde_json::json!({
+            "message": "outer error",
+            "causes": ["middle error", "inner error"],
+        })
+    );
+    assert_eq!(
+        value,
+        serde_json::json!([
+            { "message": "outer error" },
+            { "message": "middle error" },
+            { "message": "inner error" },
+        ])
+    );
+}
+
+#[cfg(feature = "backtrace")]
+#[sealed_test(env = [("RUST_BACKTRACE", "1")])]
+fn test_to_json_value_backtrace() {
+    #[derive(thiserror::Error, Debug)]
+    #[error("with backtrace")]
+    struct WithBacktrace {
+        source: Inner,
+        backtrace: std::backtrace::Backtrace,
+    }
+
+    let error = WithBacktrace {
+        source: Inner,
+        backtrace: std::backtrace::Backtrace::capture(),
+    };
+    let value = error.as_report().to_json_value();
+    assert!(value[0]["backtrace"].is_string());
+    assert!(value[1].get("backtrace").is_none());
+
+    let value = serde_json::to_value(error.as_report()).unwrap();
+    assert!(value["backtrace"].is_string());
+}
+
+#[cfg(feature = "backtrace")]
+#[sealed_test(env = [("RUST_BACKTRACE", "0")])]
+fn test_to_json_value_disabled_backtrace() {
+    #[derive(thiserror::Error, Debug)]
+    #[error("with backtrace")]
+    struct WithBacktrace {
+        source: Inner,
+        backtrace: std::backtrace::Backtrace,
+    }
+
+    let error = WithBacktrace {
+        source: Inner,
+        backtrace: std::backtrace::Backtrace::capture(),
+    };
+    let value = error.as_report().to_json_value();
+    assert!(value[0].get("backtrace").is_none());
+
+    let value = serde_json::to_value(error.as_report()).unwrap();
+    assert!(value.get("backtrace").is_none());
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "tracing")]
+#![cfg_attr(feature = "backtrace", feature(error_generic_member_access))]
+
+use thiserror::Error;
+use thiserror_ext::{Arc, Box};
+#[cfg(feature = "backtrace")]
+use tracing_error::SpanTrace;
+use tracing_error::{ErrorLayer, SpanTraceStatus};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[derive(Error, Debug, Box)]
+#[thiserror_ext(newtype(name = MyError, spantrace))]
+enum MyErrorKind {
+    #[error("not found")]
+    NotFound,
+}
+
+#[derive(Error, Debug, Arc)]
+#[thiserror_ext(newtype(name = MySharedError, spantrace))]
+enum MySharedErrorKind {
+    #[error("not found")]
+    NotFound,
+}
+
+fn with_subscriber(f: impl FnOnce()) {
+    let subscriber = tracing_subscriber::registry().with(ErrorLayer::default());
+    tracing::subscriber::with_default(subscriber, f);
+}
+
+#[test]
+fn test_span_trace_captured() {
+    with_subscriber(|| {
+        let _span = tracing::info_span!("handle_request").entered();
+
+        let error: MyError = MyErrorKind::NotFound.into();
+        let span_trace = error.span_trace().unwrap();
+        assert_eq!(span_trace.status(), SpanTraceStatus::CAPTURED);
+        assert!(span_trace.to_string().contains("handle_request"));
+
+        let error: MySharedError = MySharedErrorKind::NotFound.into();
+        let span_trace = error.clone().span_trace().unwrap().to_string();
+        assert!(span_trace.contains("handle_request"));
+    });
+}
+
+#[test]
+fn test_span_trace_not_captured_without_layer() {
+    let error: MyError = MyErrorKind::NotFound.into();
+    let span_trace = error.span_trace().unwrap();
+    assert_ne!(span_trace.status(), SpanTraceStatus::CAPTURED);
+}
+
+#[cfg(feature = "backtrace")]
+#[test]
+fn test_span_trace_provided() {
+    with_subscriber(|| {
+        let _span = tracing::info_span!("handle_request").entered();
+
+        let error: MyError = MyErrorKind::NotFound.into();
+        let span_trace = std::error::request_ref::<SpanTrace>(&error).unwrap();
+        assert!(span_trace.to_string().contains("handle_request"));
+
+        // Not captured again when wrapping an error that already provides one.
+        #[derive(Error, Debug, Box)]
+        #[thiserror_ext(newtype(name = OuterError, spantrace))]
+        enum OuterErrorKind {
+            #[error("outer")]
+            Outer(
+                #[from]
+                #[backtrace]
+                MyError,
+            ),
+        }
+
+        let outer: OuterError = error.into();
+        assert!(outer.span_trace().is_none());
+        assert!(std::error::request_ref::<SpanTrace>(&outer).is_some());
+    });
+}